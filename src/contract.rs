@@ -1,167 +1,1760 @@
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+
 use cosmwasm_std::{
-    to_binary, Api, Binary, Env, Extern, HandleResponse, InitResponse, Querier, StdError,
-    StdResult, Storage,
+    log, to_binary, Api, Binary, CanonicalAddr, Coin, CosmosMsg, Env, Extern, HandleResponse,
+    HumanAddr, InitResponse, Querier, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+
+use crate::math;
+use crate::msg::{
+    AliasResponse, AttestationResponse, AuditTrailResponse, BoundaryDistanceResponse,
+    ClassifyResponse, DefaultsResponse, DriftForecastResponse, EffectiveConfigResponse,
+    ExtremesResponse, GlyphResponse, HandleMsg, InfoResponse, InitMsg, LowDigitResponse,
+    LuckyNumberResponse, MutationStatusResponse, NormalizeAddrResponse, OwnerActionsResponse,
+    Parity, ParityBitResponse, ParityDwellResponse, ParityIfChangedResponse,
+    ParticipantCountResponse, PredicateResponse, ProbabilityHintResponse, QueryMsg,
+    ResetProposalResponse, ResidueResponse, ScheduledResetResponse, StorageStatsResponse,
+    StreakToGoalResponse, SubscriberMsg, SupportedMessagesResponse, TreasuryResponse,
+};
+use crate::state::{
+    alias, append_audit_entry, append_owner_action, audit_count, audit_entry, audit_first, config,
+    config_read, mark_participant, owner_action, owner_action_count, prune_audit_entries,
+    reset_proposal, save_alias, save_reset_proposal, AuditEntry, AuditEvent, Extremes, Flags,
+    OwnerAction, ResetProposal, ScheduledReset, State,
 };
 
-use crate::msg::{HandleMsg, InitMsg, QueryMsg};
-use crate::state::{config, config_read, State};
+// bumped whenever the message or state layout changes incompatibly
+pub const SCHEMA_VERSION: u32 = 1;
+
+// at most this many blocks of drift are applied per handler call, so a long idle
+// period cannot move the count arbitrarily far
+pub const MAX_DRIFT_BLOCKS: u64 = 10_000;
+
+// serialized names of every HandleMsg and QueryMsg variant in declaration order, the
+// message_lists_are_complete test fails when one is missing
+pub const HANDLE_MESSAGES: &[&str] = &[
+    "increase",
+    "decrease",
+    "reset",
+    "freeze_count",
+    "unfreeze_count",
+    "adjust_by_percent",
+    "flip_parity",
+    "donate",
+    "schedule_reset",
+    "cancel_scheduled_reset",
+    "increase_if_parity",
+    "renounce_ownership",
+    "import_state",
+    "prune_history",
+    "set_active_windows",
+    "set_glyphs",
+    "increase_by_deposit",
+    "set_alias",
+    "propose_reset",
+    "approve_reset",
+];
+pub const QUERY_MESSAGES: &[&str] = &[
+    "query_even_odd",
+    "query_info",
+    "query_streak_to_goal",
+    "query_defaults",
+    "query_probability_hint",
+    "query_normalize_addr",
+    "query_audit_trail",
+    "query_parity_bit",
+    "query_effective_config",
+    "query_residue",
+    "query_attestation",
+    "query_extremes",
+    "query_treasury",
+    "query_participant_count",
+    "query_classify",
+    "query_supported_messages",
+    "query_boundary_distance",
+    "query_parity_dwell",
+    "query_drift_forecast",
+    "query_owner_actions",
+    "query_predicate",
+    "query_storage_stats",
+    "query_lucky_number",
+    "query_mutation_status",
+    "query_low_digit",
+    "query_glyph",
+    "query_parity_if_changed",
+    "query_alias",
+    "query_scheduled_reset",
+    "query_reset_proposal",
+];
+
+// keeps an ImportState within the gas limit of a single transaction
+pub const MAX_IMPORT_ENTRIES: usize = 100;
+
+// oldest audit entries removed per PruneHistory call at most, call again for more
+pub const MAX_PRUNE_ENTRIES: u64 = 100;
+
+// every mutation walks the windows, so their number is bounded
+pub const MAX_ACTIVE_WINDOWS: usize = 20;
+
+pub const DEFAULT_EVEN_GLYPH: &str = "🟦 even";
+pub const DEFAULT_ODD_GLYPH: &str = "🟧 odd";
+// in chars, so that a glyph stays a label rather than a message
+pub const MAX_GLYPH_LEN: usize = 16;
+
+// in chars, like MAX_GLYPH_LEN
+pub const MAX_ALIAS_LEN: usize = 32;
+
+pub const DEFAULT_PAGE_LIMIT: u32 = 10;
+pub const MAX_PAGE_LIMIT: u32 = 30;
 
 pub fn init<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     msg: InitMsg,
 ) -> StdResult<InitResponse> {
-    let state = State {
+    if config_read(&deps.storage).may_load()?.is_some() {
+        return Err(StdError::generic_err("contract is already initialized"));
+    }
+    validate_init_msg(&msg)?;
+    let subscriber = match msg.subscriber {
+        Some(addr) => Some(deps.api.canonical_address(&addr)?),
+        None => None,
+    };
+    let notify_on_reset = match msg.notify_on_reset {
+        Some(addr) => Some(deps.api.canonical_address(&addr)?),
+        None => None,
+    };
+    let mut reset_admins = Vec::with_capacity(msg.reset_admins.len());
+    for admin in &msg.reset_admins {
+        let admin = deps.api.canonical_address(admin)?;
+        if reset_admins.contains(&admin) {
+            return Err(StdError::generic_err("reset_admins contains duplicates"));
+        }
+        reset_admins.push(admin);
+    }
+    let mut state = State {
         count: msg.count,
         owner: env.message.sender,
+        subscriber,
+        flags: Flags::default(),
+        unsigned_count: msg.count.max(0) as u64,
+        reset_cooldown_blocks: msg.reset_cooldown_blocks,
+        last_reset_height: None,
+        auto_drift_per_block: msg.auto_drift_per_block,
+        last_height: env.block.height,
+        streak: 0,
+        streak_parity: None,
+        notify_on_reset,
+        extremes: Extremes::new(msg.count),
+        unsigned_extremes: Extremes::new(msg.count.max(0) as u64),
+        activation_height: env
+            .block
+            .height
+            .saturating_add(msg.activation_delay_blocks.unwrap_or(0)),
+        treasury: vec![],
+        unique_participants: 0,
+        scheduled_reset: None,
+        even_blocks: 0,
+        odd_blocks: 0,
+        parity_since: env.block.height,
+        safe_floor: msg.safe_floor,
+        decrease_penalty: msg.decrease_penalty,
+        deposit_denom: msg.deposit_denom,
+        active_windows: vec![],
+        even_glyph: DEFAULT_EVEN_GLYPH.to_string(),
+        odd_glyph: DEFAULT_ODD_GLYPH.to_string(),
+        reset_admins,
+        reset_threshold: msg.reset_threshold,
+        next_proposal_id: 0,
     };
+    state.flags.set_unsigned(msg.unsigned);
+    state.flags.set_clamp_on_overflow(msg.clamp_on_overflow);
 
     config(&mut deps.storage).save(&state)?;
 
     Ok(InitResponse::default())
 }
 
+// Checks everything about an InitMsg that does not need chain access. Public so
+// deployment tooling can run the exact same checks before instantiating.
+pub fn validate_init_msg(msg: &InitMsg) -> StdResult<()> {
+    if msg.unsigned && msg.count < 0 {
+        return Err(StdError::generic_err(
+            "an unsigned game cannot start below zero",
+        ));
+    }
+    if msg.unsigned && msg.clamp_on_overflow {
+        return Err(StdError::generic_err(
+            "clamp_on_overflow only applies to signed games",
+        ));
+    }
+    if let Some(subscriber) = &msg.subscriber {
+        if subscriber.as_str().trim().is_empty() {
+            return Err(StdError::generic_err("subscriber address is empty"));
+        }
+    }
+    if let Some(notify) = &msg.notify_on_reset {
+        if notify.as_str().trim().is_empty() {
+            return Err(StdError::generic_err("notify_on_reset address is empty"));
+        }
+    }
+    if msg.safe_floor.is_some() != msg.decrease_penalty.is_some() {
+        return Err(StdError::generic_err(
+            "safe_floor and decrease_penalty must be set together",
+        ));
+    }
+    if msg.reset_admins.is_empty() != (msg.reset_threshold == 0) {
+        return Err(StdError::generic_err(
+            "reset_admins and reset_threshold must be set together",
+        ));
+    }
+    if msg.reset_threshold as usize > msg.reset_admins.len() {
+        return Err(StdError::generic_err(
+            "reset_threshold cannot exceed the number of reset_admins",
+        ));
+    }
+    if msg
+        .reset_admins
+        .iter()
+        .any(|admin| admin.as_str().trim().is_empty())
+    {
+        return Err(StdError::generic_err(
+            "reset_admins contains an empty address",
+        ));
+    }
+    Ok(())
+}
+
 pub fn handle<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     msg: HandleMsg,
 ) -> StdResult<HandleResponse> {
     match msg {
-        HandleMsg::Increase { value } => try_increase(deps, env, value),
+        HandleMsg::Increase { value } => try_increase(deps, env, value, None),
         HandleMsg::Decrease { value } => try_decrease(deps, env, value),
         HandleMsg::Reset { count } => try_reset(deps, env, count),
+        HandleMsg::FreezeCount {} => try_set_count_frozen(deps, env, true),
+        HandleMsg::UnfreezeCount {} => try_set_count_frozen(deps, env, false),
+        HandleMsg::AdjustByPercent { bps } => try_adjust_by_percent(deps, env, bps),
+        HandleMsg::FlipParity {} => try_flip_parity(deps, env),
+        HandleMsg::Donate {} => try_donate(deps, env),
+        HandleMsg::ScheduleReset { count, at_height } => {
+            try_schedule_reset(deps, env, count, at_height)
+        }
+        HandleMsg::CancelScheduledReset {} => try_cancel_scheduled_reset(deps, env),
+        HandleMsg::IncreaseIfParity { value, expected } => {
+            try_increase(deps, env, value, Some(expected))
+        }
+        HandleMsg::RenounceOwnership { confirm } => try_renounce_ownership(deps, env, confirm),
+        HandleMsg::ImportState { count, history } => try_import_state(deps, env, count, history),
+        HandleMsg::PruneHistory { keep_last } => try_prune_history(deps, env, keep_last),
+        HandleMsg::SetActiveWindows { windows } => try_set_active_windows(deps, env, windows),
+        HandleMsg::SetGlyphs { even, odd } => try_set_glyphs(deps, env, even, odd),
+        HandleMsg::IncreaseByDeposit {} => try_increase_by_deposit(deps, env),
+        HandleMsg::SetAlias { alias } => try_set_alias(deps, env, alias),
+        HandleMsg::ProposeReset { count } => try_propose_reset(deps, env, count),
+        HandleMsg::ApproveReset { proposal_id } => try_approve_reset(deps, env, proposal_id),
     }
 }
 
 pub fn try_increase<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
-    _env: Env,
+    env: Env,
     value: i32,
+    expected: Option<Parity>,
 ) -> StdResult<HandleResponse> {
-    config(&mut deps.storage).update(|mut state| {
-        state.count += value;
-        Ok(state)
-    })?;
+    let mut state = config_read(&deps.storage).load()?;
+    ensure_mutable(&state, &env)?;
+    let before = state.parity();
+    let fired = advance_to(&mut state, env.block.height);
+    let from = state.wide_count();
+    if let Some(expected) = expected {
+        if state.parity() != expected {
+            return Err(StdError::generic_err(format!(
+                "count is {}, expected {}",
+                state.parity().as_str(),
+                expected.as_str()
+            )));
+        }
+    }
+    let clamped = add_to_count(&mut state, value)?;
+    let event = AuditEvent::Increase { value };
+    let mut res = finish_mutation(deps, &env, before, &mut state, fired, from, event)?;
+    if clamped {
+        res.log.push(log("clamped", "true"));
+    }
+    Ok(res)
+}
 
-    Ok(HandleResponse::default())
+pub fn try_increase_by_deposit<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    let denom = state
+        .deposit_denom
+        .clone()
+        .ok_or_else(|| StdError::generic_err("no deposit denom configured"))?;
+    let amount: u128 = env
+        .message
+        .sent_funds
+        .iter()
+        .filter(|coin| coin.denom == denom)
+        .map(|coin| coin.amount.u128())
+        .sum();
+    if amount == 0 {
+        return Err(StdError::generic_err(format!(
+            "no {} sent to deposit",
+            denom
+        )));
+    }
+    let value = i32::try_from(amount)
+        .map_err(|_| StdError::generic_err("deposit exceeds the range of the count"))?;
+
+    ensure_mutable(&state, &env)?;
+    let before = state.parity();
+    let fired = advance_to(&mut state, env.block.height);
+    let from = state.wide_count();
+    let clamped = add_to_count(&mut state, value)?;
+    // other denoms attached along are kept like a donation
+    credit_treasury(&mut state.treasury, &env.message.sent_funds)?;
+    let deposit = Coin {
+        denom,
+        amount: Uint128(amount),
+    };
+    let deposited = display_coins(std::slice::from_ref(&deposit));
+    let event = AuditEvent::IncreaseByDeposit { amount: deposit };
+    let mut res = finish_mutation(deps, &env, before, &mut state, fired, from, event)?;
+    if clamped {
+        res.log.push(log("clamped", "true"));
+    }
+    res.log.push(log("deposited", deposited));
+    Ok(res)
 }
 
 pub fn try_decrease<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
-    _env: Env,
+    env: Env,
     value: i32,
 ) -> StdResult<HandleResponse> {
-    config(&mut deps.storage).update(|mut state| {
-        state.count -= value;
-        Ok(state)
-    })?;
+    let mut state = config_read(&deps.storage).load()?;
+    ensure_mutable(&state, &env)?;
+    let before = state.parity();
+    let fired = advance_to(&mut state, env.block.height);
+    let from = state.wide_count();
+    let mut clamped = false;
+    if state.flags.is_unsigned() {
+        state.unsigned_count = math::add_unsigned(state.unsigned_count, -(value as i64))?;
+    } else {
+        let (count, was_clamped) = clamp_overflow(
+            math::sub_checked(state.count, value),
+            state.count.saturating_sub(value),
+            state.flags.is_clamp_on_overflow(),
+        )?;
+        state.count = count;
+        clamped = was_clamped;
+    }
+    let event = AuditEvent::Decrease { value };
+    let mut res = finish_mutation(deps, &env, before, &mut state, fired, from, event)?;
+    if clamped {
+        res.log.push(log("clamped", "true"));
+    }
+    Ok(res)
+}
 
-    Ok(HandleResponse::default())
+pub fn try_adjust_by_percent<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    bps: i32,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    ensure_mutable(&state, &env)?;
+    let before = state.parity();
+    let fired = advance_to(&mut state, env.block.height);
+    let from = state.wide_count();
+    let mut clamped = false;
+    if state.flags.is_unsigned() {
+        state.unsigned_count = math::adjust_unsigned_by_bps(state.unsigned_count, bps)?;
+    } else {
+        let (count, was_clamped) = clamp_overflow(
+            math::adjust_by_bps(state.count, bps),
+            math::saturating_adjust_by_bps(state.count, bps),
+            state.flags.is_clamp_on_overflow(),
+        )?;
+        state.count = count;
+        clamped = was_clamped;
+    }
+    let event = AuditEvent::AdjustByPercent { bps };
+    let mut res = finish_mutation(deps, &env, before, &mut state, fired, from, event)?;
+    if clamped {
+        res.log.push(log("clamped", "true"));
+    }
+    Ok(res)
 }
 
+pub fn try_flip_parity<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    ensure_mutable(&state, &env)?;
+    let before = state.parity();
+    let fired = advance_to(&mut state, env.block.height);
+    let from = state.wide_count();
+    let step = if state.flags.is_unsigned() {
+        let step = if state.unsigned_count == u64::MAX {
+            -1
+        } else {
+            1
+        };
+        state.unsigned_count = math::add_unsigned(state.unsigned_count, step)?;
+        step as i32
+    } else {
+        let step = if state.count == i32::MAX { -1 } else { 1 };
+        state.count = math::add_checked(state.count, step)?;
+        step
+    };
+    let event = AuditEvent::FlipParity { step };
+    let mut res = finish_mutation(deps, &env, before, &mut state, fired, from, event)?;
+    res.log.push(log("action", "flip"));
+    Ok(res)
+}
 
 pub fn try_reset<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     count: i32,
 ) -> StdResult<HandleResponse> {
-    config(&mut deps.storage).update(|mut state| {
-        if env.message.sender != state.owner {
-            return Err(StdError::Unauthorized { backtrace: None });
+    let state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    ensure_owner_resets(&state)?;
+    let res = apply_reset(deps, &env, state, count)?;
+    record_owner_action(deps, &env, AuditEvent::Reset { count })?;
+    Ok(res)
+}
+
+// the owner's Reset and an approved ProposeReset
+fn apply_reset<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    mut state: State,
+    count: i32,
+) -> StdResult<HandleResponse> {
+    ensure_mutable(&state, env)?;
+    let before = state.parity();
+    // a due scheduled reset counts towards the cooldown of this one
+    let fired = advance_to(&mut state, env.block.height);
+    let from = state.wide_count();
+    if let Some(last) = state.last_reset_height {
+        let next = last.saturating_add(state.reset_cooldown_blocks);
+        if env.block.height < next {
+            return Err(StdError::generic_err(format!(
+                "reset is on cooldown, next reset allowed at block {}",
+                next
+            )));
+        }
+    }
+    // the scheduled reset would otherwise fire inside the cooldown this reset starts
+    if let Some(scheduled) = state.scheduled_reset {
+        let next = env.block.height.saturating_add(state.reset_cooldown_blocks);
+        if scheduled.at_height > env.block.height && scheduled.at_height < next {
+            return Err(StdError::generic_err(format!(
+                "a reset is scheduled for block {}, within the cooldown of this one",
+                scheduled.at_height
+            )));
+        }
+    }
+    if state.flags.is_unsigned() {
+        if count < 0 {
+            return Err(StdError::generic_err(
+                "an unsigned game cannot be reset below zero",
+            ));
         }
+        state.unsigned_count = count as u64;
+    } else {
         state.count = count;
-        Ok(state)
-    })?;
+    }
+    state.last_reset_height = Some(env.block.height);
+
+    let event = AuditEvent::Reset { count };
+    finish_mutation(deps, env, before, &mut state, fired, from, event)
+}
+
+pub fn try_schedule_reset<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    count: i32,
+    at_height: u64,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    ensure_owner_resets(&state)?;
+    if at_height <= env.block.height {
+        return Err(StdError::generic_err(
+            "a reset can only be scheduled for a future block",
+        ));
+    }
+    ensure_not_due(&state, &env)?;
+    if let Some(last) = state.last_reset_height {
+        let next = last.saturating_add(state.reset_cooldown_blocks);
+        if at_height < next {
+            return Err(StdError::generic_err(format!(
+                "reset is on cooldown, next reset allowed at block {}",
+                next
+            )));
+        }
+    }
+    if state.flags.is_unsigned() && count < 0 {
+        return Err(StdError::generic_err(
+            "an unsigned game cannot be reset below zero",
+        ));
+    }
+    state.scheduled_reset = Some(ScheduledReset { count, at_height });
+    config(&mut deps.storage).save(&state)?;
+    let event = AuditEvent::ScheduleReset { count, at_height };
+    record_audit_event(deps, &env, &state, event.clone())?;
+    record_owner_action(deps, &env, event)?;
     Ok(HandleResponse::default())
 }
 
-pub fn query<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    msg: QueryMsg,
-) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::QueryEvenOdd {} => to_binary(&query_even_odd(deps)?),
+pub fn try_cancel_scheduled_reset<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    ensure_not_due(&state, &env)?;
+    if state.scheduled_reset.take().is_none() {
+        return Err(StdError::generic_err("no reset is scheduled"));
     }
+    config(&mut deps.storage).save(&state)?;
+    record_audit_event(deps, &env, &state, AuditEvent::CancelScheduledReset {})?;
+    record_owner_action(deps, &env, AuditEvent::CancelScheduledReset {})?;
+    Ok(HandleResponse::default())
 }
 
-fn query_even_odd<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<String> {
-    let state = config_read(&deps.storage).load()?;
-    if state.count % 2 == 0 {
-        Ok(format!("Even Number: {}", state.count))
+pub fn try_renounce_ownership<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    confirm: bool,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    if !confirm {
+        return Err(StdError::generic_err(
+            "renouncing ownership cannot be undone, set confirm to true",
+        ));
+    }
+    ensure_not_due(&state, &env)?;
+    state.owner = CanonicalAddr::default();
+    // nobody is left to revisit a pending reset, so it goes with the owner
+    let cancelled = state.scheduled_reset.take().is_some();
+    config(&mut deps.storage).save(&state)?;
+    if cancelled {
+        record_audit_event(deps, &env, &state, AuditEvent::CancelScheduledReset {})?;
+        record_owner_action(deps, &env, AuditEvent::CancelScheduledReset {})?;
+    }
+    record_audit_event(deps, &env, &state, AuditEvent::RenounceOwnership {})?;
+    record_owner_action(deps, &env, AuditEvent::RenounceOwnership {})?;
+    Ok(HandleResponse::default())
+}
+
+pub fn try_import_state<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    count: i32,
+    history: Vec<AuditEntry>,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    if state.flags.is_imported() {
+        return Err(StdError::generic_err("state was already imported"));
+    }
+    // strangers can write to the audit trail, so only the count and the owner's own
+    // actions close the import
+    if state.unique_participants > 0 || owner_action_count(&deps.storage)? > 0 {
+        return Err(StdError::generic_err(
+            "state can only be imported before the count changes or the owner acts",
+        ));
+    }
+    if history.len() > MAX_IMPORT_ENTRIES {
+        return Err(StdError::generic_err(format!(
+            "cannot import more than {} history entries",
+            MAX_IMPORT_ENTRIES
+        )));
+    }
+    let before = state.parity();
+    let fired = advance_to(&mut state, env.block.height);
+    let from = state.wide_count();
+    if state.flags.is_unsigned() {
+        if count < 0 {
+            return Err(StdError::generic_err(
+                "an unsigned game cannot be reset below zero",
+            ));
+        }
+        state.unsigned_count = count as u64;
+        state.unsigned_extremes = Extremes::new(count as u64);
     } else {
-        Ok(format!("Odd Number: {}", state.count))
+        state.count = count;
+        state.extremes = Extremes::new(count);
     }
+    state.flags.set_imported(true);
+    for entry in &history {
+        append_audit_entry(&mut deps.storage, entry)?;
+    }
+    let event = AuditEvent::ImportState {
+        count,
+        entries: history.len() as u64,
+    };
+    let res = finish_mutation(deps, &env, before, &mut state, fired, from, event.clone())?;
+    record_owner_action(deps, &env, event)?;
+    Ok(res)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env};
-    use cosmwasm_std::{coins, from_binary, StdError};
+pub fn try_prune_history<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    keep_last: u32,
+) -> StdResult<HandleResponse> {
+    let state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    let pruned = prune_audit_entries(&mut deps.storage, u64::from(keep_last), MAX_PRUNE_ENTRIES)?;
+    // only an owner action, an audit entry would leave keep_last + 1 entries behind
+    record_owner_action(deps, &env, AuditEvent::PruneHistory { keep_last })?;
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("pruned", pruned)],
+        data: None,
+    })
+}
 
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies(20, &[]);
+pub fn try_set_active_windows<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    windows: Vec<(u64, u64)>,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    if windows.len() > MAX_ACTIVE_WINDOWS {
+        return Err(StdError::generic_err(format!(
+            "cannot set more than {} active windows",
+            MAX_ACTIVE_WINDOWS
+        )));
+    }
+    if let Some((start, end)) = windows.iter().find(|(start, end)| start > end) {
+        return Err(StdError::generic_err(format!(
+            "active window [{}, {}] ends before it starts",
+            start, end
+        )));
+    }
+    state.active_windows = windows.clone();
+    config(&mut deps.storage).save(&state)?;
+    let event = AuditEvent::SetActiveWindows { windows };
+    record_audit_event(deps, &env, &state, event.clone())?;
+    record_owner_action(deps, &env, event)?;
+    Ok(HandleResponse::default())
+}
 
-        let msg = InitMsg { count: 17 };
-        let env = mock_env(&deps.api, "creator", &coins(1000, "earth"));
+pub fn try_set_glyphs<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    even: String,
+    odd: String,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    if env.message.sender != state.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    for glyph in &[&even, &odd] {
+        let len = glyph.chars().count();
+        if len == 0 || len > MAX_GLYPH_LEN {
+            return Err(StdError::generic_err(format!(
+                "a glyph must be 1 to {} characters long",
+                MAX_GLYPH_LEN
+            )));
+        }
+    }
+    state.even_glyph = even.clone();
+    state.odd_glyph = odd.clone();
+    config(&mut deps.storage).save(&state)?;
+    let event = AuditEvent::SetGlyphs { even, odd };
+    record_audit_event(deps, &env, &state, event.clone())?;
+    record_owner_action(deps, &env, event)?;
+    Ok(HandleResponse::default())
+}
 
-        // we can just call .unwrap() to assert this was a success
-        let res = init(&mut deps, env, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+pub fn try_set_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias: String,
+) -> StdResult<HandleResponse> {
+    let len = alias.chars().count();
+    if len == 0 || len > MAX_ALIAS_LEN {
+        return Err(StdError::generic_err(format!(
+            "an alias must be 1 to {} characters long",
+            MAX_ALIAS_LEN
+        )));
+    }
+    save_alias(&mut deps.storage, &env.message.sender, &alias)?;
+    let state = config_read(&deps.storage).load()?;
+    record_audit_event(deps, &env, &state, AuditEvent::SetAlias { alias })?;
+    Ok(HandleResponse::default())
+}
 
-        // it worked, let's query the state
-        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
-        let value: String = from_binary(&res).unwrap();
-        assert_eq!("Odd Number: 17", value);
+pub fn try_propose_reset<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    count: i32,
+) -> StdResult<HandleResponse> {
+    let mut state = config_read(&deps.storage).load()?;
+    ensure_reset_admin(&state, &env)?;
+    if state.flags.is_unsigned() && count < 0 {
+        return Err(StdError::generic_err(
+            "an unsigned game cannot be reset below zero",
+        ));
     }
+    let proposal_id = state.next_proposal_id;
+    state.next_proposal_id += 1;
+    let proposal = ResetProposal {
+        count,
+        approvals: vec![],
+        executed: false,
+    };
+    save_reset_proposal(&mut deps.storage, proposal_id, &proposal)?;
+    config(&mut deps.storage).save(&state)?;
+    let event = AuditEvent::ProposeReset { proposal_id, count };
+    record_audit_event(deps, &env, &state, event)?;
 
-    #[test]
-    fn increase() {
-        let mut deps = mock_dependencies(20, &coins(2, "token"));
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("proposal_id", proposal_id.to_string())],
+        data: None,
+    })
+}
 
-        let msg = InitMsg { count: 17 };
-        let env = mock_env(&deps.api, "creator", &coins(2, "token"));
-        let _res = init(&mut deps, env, msg).unwrap();
+pub fn try_approve_reset<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    proposal_id: u64,
+) -> StdResult<HandleResponse> {
+    let state = config_read(&deps.storage).load()?;
+    ensure_reset_admin(&state, &env)?;
+    let mut proposal = reset_proposal(&deps.storage, proposal_id)?.ok_or_else(|| {
+        StdError::generic_err(format!("no reset proposal with id {}", proposal_id))
+    })?;
+    if proposal.executed {
+        return Err(StdError::generic_err(format!(
+            "reset proposal {} was already executed",
+            proposal_id
+        )));
+    }
+    if proposal.approvals.contains(&env.message.sender) {
+        return Err(StdError::generic_err(format!(
+            "reset proposal {} is already approved by the sender",
+            proposal_id
+        )));
+    }
+    proposal.approvals.push(env.message.sender.clone());
+    proposal.executed = proposal.approvals.len() >= state.reset_threshold as usize;
+    save_reset_proposal(&mut deps.storage, proposal_id, &proposal)?;
+    record_audit_event(deps, &env, &state, AuditEvent::ApproveReset { proposal_id })?;
 
-        // beneficiary can release it
-        let env = mock_env(&deps.api, "anyone", &coins(2, "token"));
-        let msg = HandleMsg::Increase { value: 2 };
-        let _res = handle(&mut deps, env, msg).unwrap();
+    let approvals = format!("{}/{}", proposal.approvals.len(), state.reset_threshold);
+    let mut res = if proposal.executed {
+        apply_reset(deps, &env, state, proposal.count)?
+    } else {
+        HandleResponse::default()
+    };
+    res.log.push(log("approvals", approvals));
+    Ok(res)
+}
 
-        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
-        let value: String = from_binary(&res).unwrap();
-        assert_eq!("Odd Number: 19", value);
+pub fn try_donate<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> StdResult<HandleResponse> {
+    let amount: Vec<Coin> = env
+        .message
+        .sent_funds
+        .iter()
+        .filter(|coin| coin.amount.u128() > 0)
+        .cloned()
+        .collect();
+    if amount.is_empty() {
+        return Err(StdError::generic_err("no funds sent to donate"));
     }
+    let mut state = config_read(&deps.storage).load()?;
+    credit_treasury(&mut state.treasury, &amount)?;
+    config(&mut deps.storage).save(&state)?;
+    let donor = deps.api.human_address(&env.message.sender)?;
+    let donated = display_coins(&amount);
+    record_audit_event(deps, &env, &state, AuditEvent::Donate { amount })?;
 
-    #[test]
-    fn decrease() {
-        let mut deps = mock_dependencies(20, &coins(2, "token"));
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("donated", donated), log("donor", donor.as_str())],
+        data: None,
+    })
+}
 
-        let msg = InitMsg { count: 17 };
-        let env = mock_env(&deps.api, "creator", &coins(2, "token"));
-        let _res = init(&mut deps, env, msg).unwrap();
+pub fn try_set_count_frozen<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    frozen: bool,
+) -> StdResult<HandleResponse> {
+    let state = config(&mut deps.storage).update(|mut state| {
+        if env.message.sender != state.owner {
+            return Err(StdError::Unauthorized { backtrace: None });
+        }
+        // the frozen blocks accrue no drift
+        if !frozen && state.flags.is_count_frozen() {
+            state.last_height = env.block.height;
+        }
+        state.flags.set_count_frozen(frozen);
+        Ok(state)
+    })?;
+    record_audit_event(deps, &env, &state, AuditEvent::SetCountFrozen { frozen })?;
+    record_owner_action(deps, &env, AuditEvent::SetCountFrozen { frozen })?;
+    Ok(HandleResponse::default())
+}
 
-        // beneficiary can release it
-        let env = mock_env(&deps.api, "anyone", &coins(2, "token"));
-        let msg = HandleMsg::Decrease { value: 1 };
-        let _res = handle(&mut deps, env, msg).unwrap();
+// fails when the count currently cannot be changed
+fn ensure_mutable(state: &State, env: &Env) -> StdResult<()> {
+    match mutation_blocker(state, env.block.height) {
+        Some(reason) => Err(StdError::generic_err(reason)),
+        None => Ok(()),
+    }
+}
 
-        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
-        let value: String = from_binary(&res).unwrap();
-        assert_eq!("Even Number: 16", value);
+// the first reason the count cannot be changed at `height`, if any
+fn mutation_blocker(state: &State, height: u64) -> Option<String> {
+    if state.flags.is_count_frozen() {
+        return Some("count is frozen".to_string());
+    }
+    if height < state.activation_height {
+        return Some(format!(
+            "contract is not yet active, mutations allowed from block {}",
+            state.activation_height
+        ));
     }
+    if !state.active_windows.is_empty()
+        && !state
+            .active_windows
+            .iter()
+            .any(|&(start, end)| start <= height && height <= end)
+    {
+        return Some("the count can only change inside an active window".to_string());
+    }
+    None
+}
 
-    #[test]
-    fn reset() {
-        let mut deps = mock_dependencies(20, &coins(2, "token"));
+// blocks after `last_height` up to `height`, when active windows are set only those
+// inside one of them, closed periods add no drift
+fn drifting_blocks(state: &State, height: u64) -> u64 {
+    let from = state.last_height;
+    if height <= from {
+        return 0;
+    }
+    if state.active_windows.is_empty() {
+        return height - from;
+    }
+    let mut windows = state.active_windows.clone();
+    windows.sort_unstable();
+    // windows may overlap, blocks up to `counted` were already added
+    let mut counted = from;
+    let mut blocks = 0;
+    for (start, end) in windows {
+        let start = start.max(counted.saturating_add(1));
+        let end = end.min(height);
+        if start <= end {
+            blocks += end - start + 1;
+            counted = end;
+        }
+    }
+    blocks
+}
 
-        let msg = InitMsg { count: 17 };
-        let env = mock_env(&deps.api, "creator", &coins(2, "token"));
-        let _res = init(&mut deps, env, msg).unwrap();
+// adds `value` to the count of either game type, true if the result was clamped
+fn add_to_count(state: &mut State, value: i32) -> StdResult<bool> {
+    if state.flags.is_unsigned() {
+        state.unsigned_count = math::add_unsigned(state.unsigned_count, value as i64)?;
+        return Ok(false);
+    }
+    let (count, clamped) = clamp_overflow(
+        math::add_checked(state.count, value),
+        state.count.saturating_add(value),
+        state.flags.is_clamp_on_overflow(),
+    )?;
+    state.count = count;
+    Ok(clamped)
+}
 
-        // beneficiary can release it
-        let unauth_env = mock_env(&deps.api, "anyone", &coins(2, "token"));
+// applies a due scheduled reset and then the drift up to `height`, returns the reset
+// if one was applied so the caller can record it
+fn advance_to(state: &mut State, height: u64) -> Option<ScheduledReset> {
+    let fired = state
+        .scheduled_reset
+        .filter(|scheduled| height >= scheduled.at_height);
+    if let Some(scheduled) = fired {
+        if state.flags.is_unsigned() {
+            state.unsigned_count = scheduled.count as u64;
+        } else {
+            state.count = scheduled.count;
+        }
+        state.record_extremes();
+        state.last_height = state.last_height.max(scheduled.at_height);
+        state.last_reset_height = Some(scheduled.at_height);
+        state.scheduled_reset = None;
+    }
+    apply_drift(state, height);
+    fired
+}
+
+// a due reset is applied by the next change to the count, until then it can no longer
+// be replaced or cancelled
+fn ensure_not_due(state: &State, env: &Env) -> StdResult<()> {
+    match state.scheduled_reset {
+        Some(scheduled) if env.block.height >= scheduled.at_height => {
+            Err(StdError::generic_err(format!(
+                "the reset scheduled for block {} is already due",
+                scheduled.at_height
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+// once reset admins are configured no single account can reset
+fn ensure_owner_resets(state: &State) -> StdResult<()> {
+    if state.reset_threshold > 0 {
+        return Err(StdError::generic_err(format!(
+            "resets need the approval of {} reset admins, use ProposeReset",
+            state.reset_threshold
+        )));
+    }
+    Ok(())
+}
+
+fn ensure_reset_admin(state: &State, env: &Env) -> StdResult<()> {
+    if !state.reset_admins.contains(&env.message.sender) {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    Ok(())
+}
+
+// moves the count by the drift accumulated since the last handler that changed it,
+// saturating at the ends of the count range
+fn apply_drift(state: &mut State, height: u64) {
+    let blocks = drifting_blocks(state, height).min(MAX_DRIFT_BLOCKS);
+    state.last_height = height;
+    if state.auto_drift_per_block == 0 || blocks == 0 {
+        return;
+    }
+    let drift = i64::from(state.auto_drift_per_block) * blocks as i64;
+    if state.flags.is_unsigned() {
+        let count = i128::from(state.unsigned_count) + i128::from(drift);
+        state.unsigned_count = count.max(0).min(i128::from(u64::MAX)) as u64;
+    } else {
+        let count = i64::from(state.count) + drift;
+        state.count = count.max(i64::from(i32::MIN)).min(i64::from(i32::MAX)) as i32;
+    }
+}
+
+// picks the saturated result when clamping is enabled, returns whether it did
+fn clamp_overflow(checked: StdResult<i32>, saturated: i32, clamp: bool) -> StdResult<(i32, bool)> {
+    match checked {
+        Ok(count) => Ok((count, false)),
+        Err(_) if clamp => Ok((saturated, true)),
+        Err(err) => Err(err),
+    }
+}
+
+// checks that a decrease from `from` to the current count paid the safe floor penalty,
+// if any is due, and credits the attached funds to the treasury
+fn collect_decrease_penalty(state: &mut State, env: &Env, from: i128) -> StdResult<Option<Coin>> {
+    let (floor, per_unit) = match (state.safe_floor, &state.decrease_penalty) {
+        (Some(floor), Some(per_unit)) => (i128::from(floor), per_unit),
+        _ => return Ok(None),
+    };
+    // only the units newly below the floor count, a change starting below it pays for
+    // all of its own
+    let below = |count: i128| (floor - count).max(0);
+    let units = below(state.wide_count()) - below(from);
+    if units <= 0 {
+        return Ok(None);
+    }
+    let fee = per_unit
+        .amount
+        .u128()
+        .checked_mul(units as u128)
+        .ok_or_else(|| StdError::generic_err("decrease penalty overflow"))?;
+    let fee = Coin {
+        denom: per_unit.denom.clone(),
+        amount: Uint128(fee),
+    };
+    let paid: u128 = env
+        .message
+        .sent_funds
+        .iter()
+        .filter(|coin| coin.denom == fee.denom)
+        .map(|coin| coin.amount.u128())
+        .sum();
+    if paid < fee.amount.u128() {
+        return Err(StdError::generic_err(format!(
+            "decreasing below the safe floor requires a fee of {}",
+            display_coins(&[fee])
+        )));
+    }
+    // anything attached beyond the fee is kept like a donation
+    credit_treasury(&mut state.treasury, &env.message.sent_funds)?;
+    Ok(Some(fee))
+}
+
+// zero amounts are skipped so they never show up as empty treasury entries
+fn credit_treasury(treasury: &mut Vec<Coin>, amount: &[Coin]) -> StdResult<()> {
+    for coin in amount.iter().filter(|coin| coin.amount.u128() > 0) {
+        match treasury.iter_mut().find(|held| held.denom == coin.denom) {
+            Some(held) => {
+                let total = held
+                    .amount
+                    .u128()
+                    .checked_add(coin.amount.u128())
+                    .ok_or_else(|| StdError::generic_err("treasury overflow"))?;
+                held.amount = Uint128(total);
+            }
+            None => treasury.push(coin.clone()),
+        }
+    }
+    Ok(())
+}
+
+// "100uscrt,5uatom"
+fn display_coins(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| format!("{}{}", coin.amount, coin.denom))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// bookkeeping shared by every handler that changes the count, `before` is the
+// parity at the start of the handler
+fn finish_mutation<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    before: Parity,
+    state: &mut State,
+    fired: Option<ScheduledReset>,
+    from: i128,
+    event: AuditEvent,
+) -> StdResult<HandleResponse> {
+    // `from` is the count after drift and any due scheduled reset, the sender only pays
+    // for their own change, and an owner Reset or ImportState pays nothing
+    let penalty = match event {
+        AuditEvent::Reset { .. } | AuditEvent::ImportState { .. } => None,
+        _ => collect_decrease_penalty(state, env, from)?,
+    };
+    let after = state.parity();
+    if after != before {
+        let blocks = env.block.height.saturating_sub(state.parity_since);
+        match before {
+            Parity::Even => state.even_blocks = state.even_blocks.saturating_add(blocks),
+            Parity::Odd => state.odd_blocks = state.odd_blocks.saturating_add(blocks),
+        }
+        state.parity_since = env.block.height;
+    }
+    if state.streak_parity == Some(after) {
+        state.streak = state.streak.saturating_add(1);
+    } else {
+        state.streak_parity = Some(after);
+        state.streak = 1;
+    }
+    state.record_extremes();
+    if mark_participant(&mut deps.storage, &env.message.sender)? {
+        state.unique_participants += 1;
+    }
+    config(&mut deps.storage).save(state)?;
+    let reset = match event {
+        AuditEvent::Reset { .. } | AuditEvent::ImportState { .. } => true,
+        _ => fired.is_some(),
+    };
+    // the applied reset is recorded at the height it took effect, like last_reset_height
+    if let Some(scheduled) = fired {
+        let applied = AuditEvent::Reset {
+            count: scheduled.count,
+        };
+        let entry = AuditEntry {
+            height: scheduled.at_height,
+            sender: deps.api.human_address(&env.message.sender)?,
+            event: applied.clone(),
+            count: scheduled.count.to_string(),
+        };
+        append_audit_entry(&mut deps.storage, &entry)?;
+        let action = OwnerAction {
+            height: scheduled.at_height,
+            action: applied,
+        };
+        append_owner_action(&mut deps.storage, &action)?;
+    }
+    record_audit_event(deps, env, state, event)?;
+
+    let mut res = parity_change_response(&deps.api, before, state)?;
+    if let Some(penalty) = penalty {
+        res.log.push(log("penalty", display_coins(&[penalty])));
+    }
+    // The bank module rejects zero-amount sends, so watchers get a log entry instead.
+    // Operators who need a transfer they can subscribe to can send a dust amount
+    // themselves in the same transaction.
+    if let (true, Some(notify)) = (reset, &state.notify_on_reset) {
+        let notify = deps.api.human_address(notify)?;
+        res.log.push(log("notify_on_reset", notify.as_str()));
+    }
+    Ok(res)
+}
+
+fn record_audit_event<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    state: &State,
+    event: AuditEvent,
+) -> StdResult<()> {
+    let entry = AuditEntry {
+        height: env.block.height,
+        sender: deps.api.human_address(&env.message.sender)?,
+        event,
+        count: state.display_count(),
+    };
+    append_audit_entry(&mut deps.storage, &entry)
+}
+
+fn record_owner_action<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    action: AuditEvent,
+) -> StdResult<()> {
+    let action = OwnerAction {
+        height: env.block.height,
+        action,
+    };
+    append_owner_action(&mut deps.storage, &action)
+}
+
+// notifies the subscriber, if any, when the parity differs from `before`
+fn parity_change_response<A: Api>(
+    api: &A,
+    before: Parity,
+    state: &State,
+) -> StdResult<HandleResponse> {
+    let mut res = HandleResponse::default();
+    let after = state.parity();
+    if after != before {
+        if let Some(subscriber) = &state.subscriber {
+            res.messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: api.human_address(subscriber)?,
+                msg: to_binary(&SubscriberMsg::ParityChanged {
+                    new_parity: after,
+                    count: state.display_count(),
+                })?,
+                send: vec![],
+            }));
+        }
+    }
+    Ok(res)
+}
+
+// like config_read().load() but with an error that says what is wrong
+fn load_state<S: Storage>(storage: &S) -> StdResult<State> {
+    config_read(storage)
+        .may_load()?
+        .ok_or_else(|| StdError::generic_err("contract not initialized"))
+}
+
+pub fn query<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::QueryEvenOdd {} => to_binary(&query_even_odd(deps)?),
+        QueryMsg::QueryInfo {} => to_binary(&query_info()),
+        QueryMsg::QueryStreakToGoal { goal } => to_binary(&query_streak_to_goal(deps, goal)?),
+        QueryMsg::QueryDefaults {} => to_binary(&query_defaults()),
+        QueryMsg::QueryProbabilityHint {} => to_binary(&query_probability_hint(deps)?),
+        QueryMsg::QueryNormalizeAddr { addr } => to_binary(&query_normalize_addr(deps, addr)?),
+        QueryMsg::QueryParityBit {} => to_binary(&query_parity_bit(deps)?),
+        QueryMsg::QueryEffectiveConfig {} => to_binary(&query_effective_config(deps)?),
+        QueryMsg::QueryResidue { modulus } => to_binary(&query_residue(deps, modulus)?),
+        QueryMsg::QueryAttestation {} => to_binary(&query_attestation(deps)?),
+        QueryMsg::QueryExtremes {} => to_binary(&query_extremes(deps)?),
+        QueryMsg::QueryTreasury {} => to_binary(&query_treasury(deps)?),
+        QueryMsg::QueryParticipantCount {} => to_binary(&query_participant_count(deps)?),
+        QueryMsg::QueryClassify {} => to_binary(&query_classify(deps)?),
+        QueryMsg::QuerySupportedMessages {} => to_binary(&query_supported_messages()),
+        QueryMsg::QueryBoundaryDistance {} => to_binary(&query_boundary_distance(deps)?),
+        QueryMsg::QueryParityDwell {} => to_binary(&query_parity_dwell(deps)?),
+        QueryMsg::QueryDriftForecast { blocks } => to_binary(&query_drift_forecast(deps, blocks)?),
+        QueryMsg::QueryOwnerActions { start, limit } => {
+            to_binary(&query_owner_actions(deps, start, limit)?)
+        }
+        QueryMsg::QueryPredicate {
+            min,
+            max,
+            parity,
+            divisible_by,
+        } => to_binary(&query_predicate(deps, min, max, parity, divisible_by)?),
+        QueryMsg::QueryStorageStats {} => to_binary(&query_storage_stats(deps)?),
+        QueryMsg::QueryLuckyNumber { address } => to_binary(&query_lucky_number(deps, address)?),
+        QueryMsg::QueryMutationStatus { height } => {
+            to_binary(&query_mutation_status(deps, height)?)
+        }
+        QueryMsg::QueryLowDigit {} => to_binary(&query_low_digit(deps)?),
+        QueryMsg::QueryGlyph {} => to_binary(&query_glyph(deps)?),
+        QueryMsg::QueryAlias { address } => to_binary(&query_alias(deps, address)?),
+        QueryMsg::QueryScheduledReset { height } => {
+            to_binary(&query_scheduled_reset(deps, height)?)
+        }
+        QueryMsg::QueryResetProposal { proposal_id } => {
+            to_binary(&query_reset_proposal(deps, proposal_id)?)
+        }
+        QueryMsg::QueryParityIfChanged { since_count } => {
+            to_binary(&query_parity_if_changed(deps, since_count)?)
+        }
+        QueryMsg::QueryAuditTrail { start, limit } => {
+            to_binary(&query_audit_trail(deps, start, limit)?)
+        }
+    }
+}
+
+fn query_defaults() -> DefaultsResponse {
+    DefaultsResponse {
+        init_msg: InitMsg::default(),
+        max_drift_blocks: MAX_DRIFT_BLOCKS,
+        schema_version: SCHEMA_VERSION,
+    }
+}
+
+fn query_probability_hint<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<ProbabilityHintResponse> {
+    let state = load_state(&deps.storage)?;
+    // a frozen count cannot change, so its current parity is certain
+    let (even_pct, odd_pct) = match (state.flags.is_count_frozen(), state.parity()) {
+        (false, _) => (50, 50),
+        (true, Parity::Even) => (100, 0),
+        (true, Parity::Odd) => (0, 100),
+    };
+    Ok(ProbabilityHintResponse { even_pct, odd_pct })
+}
+
+fn query_normalize_addr<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    addr: HumanAddr,
+) -> StdResult<NormalizeAddrResponse> {
+    let canonical = deps
+        .api
+        .canonical_address(&addr)
+        .map_err(|err| StdError::parse_err("HumanAddr", err))?;
+    let human = deps.api.human_address(&canonical)?;
+    Ok(NormalizeAddrResponse { canonical, human })
+}
+
+fn query_audit_trail<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AuditTrailResponse> {
+    let (first, total) = (audit_first(&deps.storage)?, audit_count(&deps.storage)?);
+    let events = newest_first(first, total, start, limit)
+        .map(|index| audit_entry(&deps.storage, index))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AuditTrailResponse { events })
+}
+
+fn query_owner_actions<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OwnerActionsResponse> {
+    let actions = newest_first(0, owner_action_count(&deps.storage)?, start, limit)
+        .map(|index| owner_action(&deps.storage, index))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(OwnerActionsResponse { actions })
+}
+
+// indices of a page of an append-only log holding entries `first..total`, newest
+// first, skipping the `start` newest
+fn newest_first(
+    first: u64,
+    total: u64,
+    start: Option<u64>,
+    limit: Option<u32>,
+) -> impl Iterator<Item = u64> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+    let newest = total.saturating_sub(start.unwrap_or(0)).max(first);
+    let oldest = newest.saturating_sub(u64::from(limit)).max(first);
+    (oldest..newest).rev()
+}
+
+fn query_parity_bit<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<ParityBitResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(ParityBitResponse {
+        bit: state.parity().bit(),
+    })
+}
+
+fn query_effective_config<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<EffectiveConfigResponse> {
+    let state = load_state(&deps.storage)?;
+    let humanize = |addr: &Option<CanonicalAddr>| match addr {
+        Some(addr) => deps.api.human_address(addr).map(Some),
+        None => Ok(None),
+    };
+    let (min_count, max_count) = if state.flags.is_unsigned() {
+        (u64::MIN.to_string(), u64::MAX.to_string())
+    } else {
+        (i32::MIN.to_string(), i32::MAX.to_string())
+    };
+    Ok(EffectiveConfigResponse {
+        owner: if state.owner.is_empty() {
+            None
+        } else {
+            Some(deps.api.human_address(&state.owner)?)
+        },
+        subscriber: humanize(&state.subscriber)?,
+        notify_on_reset: humanize(&state.notify_on_reset)?,
+        unsigned: state.flags.is_unsigned(),
+        clamp_on_overflow: state.flags.is_clamp_on_overflow(),
+        count_frozen: state.flags.is_count_frozen(),
+        min_count,
+        max_count,
+        reset_cooldown_blocks: state.reset_cooldown_blocks,
+        next_reset_height: state
+            .last_reset_height
+            .map_or(0, |last| last.saturating_add(state.reset_cooldown_blocks)),
+        auto_drift_per_block: state.auto_drift_per_block,
+        max_drift_blocks: MAX_DRIFT_BLOCKS,
+        safe_floor: state.safe_floor,
+        decrease_penalty: state.decrease_penalty,
+        deposit_denom: state.deposit_denom,
+        active_windows: state.active_windows,
+        reset_threshold: state.reset_threshold,
+        reset_admins: state
+            .reset_admins
+            .iter()
+            .map(|admin| deps.api.human_address(admin))
+            .collect::<StdResult<Vec<HumanAddr>>>()?,
+    })
+}
+
+fn query_residue<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    modulus: i32,
+) -> StdResult<ResidueResponse> {
+    if modulus <= 0 {
+        return Err(StdError::generic_err("modulus must be positive"));
+    }
+    let state = load_state(&deps.storage)?;
+    let residue = if state.flags.is_unsigned() {
+        // below modulus, so it fits
+        (state.unsigned_count % modulus as u64) as i32
+    } else {
+        state.count.rem_euclid(modulus)
+    };
+    Ok(ResidueResponse { residue })
+}
+
+fn query_attestation<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<AttestationResponse> {
+    let state = load_state(&deps.storage)?;
+    let count = state.display_count();
+    let parity = state.parity();
+    let preimage = format!("{}|{}|{}", count, parity.as_str(), state.last_height);
+    Ok(AttestationResponse {
+        count,
+        parity,
+        height: state.last_height,
+        hash: Binary(Sha256::digest(preimage.as_bytes()).to_vec()),
+    })
+}
+
+fn query_extremes<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<ExtremesResponse> {
+    let state = load_state(&deps.storage)?;
+    let (min, max) = if state.flags.is_unsigned() {
+        let Extremes { min, max } = state.unsigned_extremes;
+        (
+            (min.to_string(), Parity::of_unsigned(min)),
+            (max.to_string(), Parity::of_unsigned(max)),
+        )
+    } else {
+        let Extremes { min, max } = state.extremes;
+        (
+            (min.to_string(), Parity::of(min)),
+            (max.to_string(), Parity::of(max)),
+        )
+    };
+    Ok(ExtremesResponse {
+        max: max.0,
+        max_parity: max.1,
+        min: min.0,
+        min_parity: min.1,
+    })
+}
+
+fn query_treasury<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<TreasuryResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(TreasuryResponse {
+        treasury: state.treasury,
+    })
+}
+
+fn query_participant_count<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<ParticipantCountResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(ParticipantCountResponse {
+        unique_participants: state.unique_participants,
+    })
+}
+
+fn query_classify<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<ClassifyResponse> {
+    let state = load_state(&deps.storage)?;
+    let magnitude = if state.flags.is_unsigned() {
+        Some(state.unsigned_count)
+    } else {
+        u64::try_from(state.count).ok()
+    };
+    Ok(ClassifyResponse {
+        parity: state.parity(),
+        is_prime: magnitude.map(math::is_prime).unwrap_or(false),
+        is_perfect_square: magnitude.map(math::is_perfect_square).unwrap_or(false),
+    })
+}
+
+fn query_streak_to_goal<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    goal: u32,
+) -> StdResult<StreakToGoalResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(StreakToGoalResponse {
+        streak: state.streak,
+        remaining: goal.saturating_sub(state.streak),
+    })
+}
+
+fn query_boundary_distance<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<BoundaryDistanceResponse> {
+    let state = load_state(&deps.storage)?;
+    let (to_max, to_min) = if state.flags.is_unsigned() {
+        (u64::MAX - state.unsigned_count, state.unsigned_count)
+    } else {
+        let count = i64::from(state.count);
+        (
+            (i64::from(i32::MAX) - count) as u64,
+            (count - i64::from(i32::MIN)) as u64,
+        )
+    };
+    Ok(BoundaryDistanceResponse {
+        to_max: to_max.to_string(),
+        to_min: to_min.to_string(),
+    })
+}
+
+fn query_parity_dwell<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<ParityDwellResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(ParityDwellResponse {
+        even_blocks: state.even_blocks,
+        odd_blocks: state.odd_blocks,
+    })
+}
+
+fn query_drift_forecast<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    blocks: u64,
+) -> StdResult<DriftForecastResponse> {
+    let mut state = load_state(&deps.storage)?;
+    let height = state.last_height.saturating_add(blocks);
+    advance_to(&mut state, height);
+    Ok(DriftForecastResponse {
+        count: state.display_count(),
+        parity: state.parity(),
+    })
+}
+
+fn query_predicate<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    min: Option<i32>,
+    max: Option<i32>,
+    parity: Option<Parity>,
+    divisible_by: Option<i32>,
+) -> StdResult<PredicateResponse> {
+    if divisible_by == Some(0) {
+        return Err(StdError::generic_err("divisible_by must not be zero"));
+    }
+    let state = load_state(&deps.storage)?;
+    // wide enough for i32::MIN % -1 too
+    let count = state.wide_count();
+    let matches = min.map(|min| count >= i128::from(min)).unwrap_or(true)
+        && max.map(|max| count <= i128::from(max)).unwrap_or(true)
+        && parity
+            .map(|parity| state.parity() == parity)
+            .unwrap_or(true)
+        && divisible_by
+            .map(|divisor| count % i128::from(divisor) == 0)
+            .unwrap_or(true);
+    Ok(PredicateResponse { matches })
+}
+
+fn query_storage_stats<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<StorageStatsResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(StorageStatsResponse {
+        audit_entries: audit_count(&deps.storage)? - audit_first(&deps.storage)?,
+        owner_actions: owner_action_count(&deps.storage)?,
+        participants: state.unique_participants,
+    })
+}
+
+fn query_lucky_number<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    address: HumanAddr,
+) -> StdResult<LuckyNumberResponse> {
+    let state = load_state(&deps.storage)?;
+    let canonical = deps.api.canonical_address(&address)?;
+    let mut hasher = Sha256::new();
+    hasher.update(canonical.as_slice());
+    hasher.update(state.display_count().as_bytes());
+    let hash = hasher.finalize();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&hash[..8]);
+    let number = (u64::from_be_bytes(prefix) % 100 + 1) as u8;
+    Ok(LuckyNumberResponse {
+        number,
+        parity: Parity::of_unsigned(u64::from(number)),
+    })
+}
+
+fn query_mutation_status<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    height: u64,
+) -> StdResult<MutationStatusResponse> {
+    let state = load_state(&deps.storage)?;
+    let reason = mutation_blocker(&state, height);
+    Ok(MutationStatusResponse {
+        allowed: reason.is_none(),
+        reason,
+    })
+}
+
+fn query_low_digit<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<LowDigitResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(LowDigitResponse {
+        digit: state.wide_count().rem_euclid(10) as u8,
+    })
+}
+
+fn query_glyph<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<GlyphResponse> {
+    let state = load_state(&deps.storage)?;
+    let glyph = match state.parity() {
+        Parity::Even => state.even_glyph,
+        Parity::Odd => state.odd_glyph,
+    };
+    Ok(GlyphResponse { glyph })
+}
+
+fn query_parity_if_changed<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    since_count: i32,
+) -> StdResult<ParityIfChangedResponse> {
+    let state = load_state(&deps.storage)?;
+    Ok(ParityIfChangedResponse {
+        changed: state.wide_count() != i128::from(since_count),
+        count: state.display_count(),
+        parity: state.parity(),
+    })
+}
+
+fn query_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    address: HumanAddr,
+) -> StdResult<AliasResponse> {
+    let canonical = deps.api.canonical_address(&address)?;
+    Ok(AliasResponse {
+        alias: alias(&deps.storage, &canonical)?,
+    })
+}
+
+fn query_scheduled_reset<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    height: u64,
+) -> StdResult<ScheduledResetResponse> {
+    let scheduled = load_state(&deps.storage)?.scheduled_reset;
+    Ok(ScheduledResetResponse {
+        pending: scheduled.is_some(),
+        at_height: scheduled.map(|reset| reset.at_height),
+        blocks_remaining: scheduled.map(|reset| reset.at_height.saturating_sub(height)),
+        new_count: scheduled.map(|reset| reset.count),
+    })
+}
+
+fn query_reset_proposal<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    proposal_id: u64,
+) -> StdResult<ResetProposalResponse> {
+    let state = load_state(&deps.storage)?;
+    let proposal = reset_proposal(&deps.storage, proposal_id)?.ok_or_else(|| {
+        StdError::generic_err(format!("no reset proposal with id {}", proposal_id))
+    })?;
+    let approvals = proposal
+        .approvals
+        .iter()
+        .map(|admin| deps.api.human_address(admin))
+        .collect::<StdResult<Vec<HumanAddr>>>()?;
+    Ok(ResetProposalResponse {
+        count: proposal.count,
+        approvals,
+        threshold: state.reset_threshold,
+        executed: proposal.executed,
+    })
+}
+
+fn query_supported_messages() -> SupportedMessagesResponse {
+    SupportedMessagesResponse {
+        handle: HANDLE_MESSAGES
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        query: QUERY_MESSAGES.iter().map(|name| name.to_string()).collect(),
+    }
+}
+
+fn query_info() -> InfoResponse {
+    InfoResponse {
+        name: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: SCHEMA_VERSION,
+    }
+}
+
+fn query_even_odd<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> StdResult<String> {
+    let state = load_state(&deps.storage)?;
+    match state.parity() {
+        Parity::Even => Ok(format!("Even Number: {}", state.display_count())),
+        Parity::Odd => Ok(format!("Odd Number: {}", state.display_count())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, from_binary, from_slice, StdError};
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = init(&mut deps, env, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // it worked, let's query the state
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 17", value);
+    }
+
+    #[test]
+    fn increase() {
+        let mut deps = mock_dependencies(20, &coins(2, "token"));
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &coins(2, "token"));
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // beneficiary can release it
+        let env = mock_env(&deps.api, "anyone", &coins(2, "token"));
+        let msg = HandleMsg::Increase { value: 2 };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 19", value);
+    }
+
+    #[test]
+    fn decrease() {
+        let mut deps = mock_dependencies(20, &coins(2, "token"));
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &coins(2, "token"));
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // beneficiary can release it
+        let env = mock_env(&deps.api, "anyone", &coins(2, "token"));
+        let msg = HandleMsg::Decrease { value: 1 };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 16", value);
+    }
+
+    #[test]
+    fn reset() {
+        let mut deps = mock_dependencies(20, &coins(2, "token"));
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &coins(2, "token"));
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // beneficiary can release it
+        let unauth_env = mock_env(&deps.api, "anyone", &coins(2, "token"));
         let msg = HandleMsg::Reset { count: 5 };
         let res = handle(&mut deps, unauth_env, msg);
         match res {
@@ -179,4 +1772,2486 @@ mod tests {
         let value: String = from_binary(&res).unwrap();
         assert_eq!("Odd Number: 5", value);
     }
+
+    #[test]
+    fn subscriber_notified_on_parity_change() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            subscriber: Some(HumanAddr::from("dashboard")),
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // 17 -> 19 keeps the parity, nothing to notify
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 2 }).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // 19 -> 20 flips it
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let expected = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("dashboard"),
+            msg: to_binary(&SubscriberMsg::ParityChanged {
+                new_parity: Parity::Even,
+                count: "20".to_string(),
+            })
+            .unwrap(),
+            send: vec![],
+        });
+        assert_eq!(vec![expected], res.messages);
+    }
+
+    #[test]
+    fn no_notification_without_subscriber() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        assert_eq!(0, res.messages.len());
+    }
+
+    #[test]
+    fn unsigned_rejects_going_below_zero() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 1,
+            unsigned: true,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Decrease { value: 2 });
+        match res {
+            Err(StdError::GenericErr { .. }) => {}
+            _ => panic!("Must reject going below zero"),
+        }
+
+        // the failed decrease left the count untouched
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 1", value);
+    }
+
+    #[test]
+    fn unsigned_parity_of_large_count() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 0,
+            unsigned: true,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // far beyond the i32 range
+        config(&mut deps.storage)
+            .update(|mut state| {
+                state.unsigned_count = u64::MAX - 1;
+                Ok(state)
+            })
+            .unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 18446744073709551614", value);
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 18446744073709551615", value);
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let res = from_slice::<HandleMsg>(br#"{"increase":{"value":2,"extra":1}}"#);
+        match res {
+            Err(StdError::ParseErr { .. }) => {}
+            _ => panic!("Must return parse error"),
+        }
+
+        let res = from_slice::<InitMsg>(br#"{"count":17,"owner":"creator"}"#);
+        match res {
+            Err(StdError::ParseErr { .. }) => {}
+            _ => panic!("Must return parse error"),
+        }
+
+        // entries imported through ImportState are checked as well
+        let nested: [&[u8]; 2] = [
+            br#"{"import_state":{"count":4,"history":[{"height":1,"sender":"creator","event":{"reset":{"count":4}},"count":"4","extra":1}]}}"#,
+            br#"{"import_state":{"count":4,"history":[{"height":1,"sender":"creator","event":{"reset":{"count":4,"extra":1}},"count":"4"}]}}"#,
+        ];
+        for msg in nested.iter() {
+            match from_slice::<HandleMsg>(msg) {
+                Err(StdError::ParseErr { .. }) => {}
+                _ => panic!("Must return parse error"),
+            }
+        }
+
+        // well-formed messages still go through
+        let msg: HandleMsg = from_slice(br#"{"increase":{"value":2}}"#).unwrap();
+        assert_eq!(HandleMsg::Increase { value: 2 }, msg);
+    }
+
+    #[test]
+    fn info() {
+        let deps = mock_dependencies(20, &[]);
+
+        // does not depend on the contract being initialized
+        let res = query(&deps, QueryMsg::QueryInfo {}).unwrap();
+        let value: InfoResponse = from_binary(&res).unwrap();
+        assert_eq!("secret-contract-odd-even-game", value.name);
+        assert_eq!(env!("CARGO_PKG_VERSION"), value.version);
+        assert_eq!(SCHEMA_VERSION, value.schema_version);
+    }
+
+    #[test]
+    fn clamp_on_overflow() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: i32::MAX - 1,
+            clamp_on_overflow: true,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 5 }).unwrap();
+        assert_eq!(vec![log("clamped", "true")], res.log);
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!(format!("Odd Number: {}", i32::MAX), value);
+    }
+
+    #[test]
+    fn overflow_errors_without_clamping() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: i32::MAX - 1,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 5 });
+        match res {
+            Err(StdError::GenericErr { .. }) => {}
+            _ => panic!("Must return overflow error"),
+        }
+    }
+
+    #[test]
+    fn reset_cooldown() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            reset_cooldown_blocks: 10,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: 5 }).unwrap();
+
+        // still within the cooldown window
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 105;
+        let res = handle(&mut deps, env, HandleMsg::Reset { count: 6 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("reset is on cooldown, next reset allowed at block 110", msg)
+            }
+            _ => panic!("Must return cooldown error"),
+        }
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 110;
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: 6 }).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 6", value);
+    }
+
+    #[test]
+    fn init_msg_validation() {
+        let valid = InitMsg {
+            count: 17,
+            subscriber: Some(HumanAddr::from("dashboard")),
+            reset_cooldown_blocks: 10,
+            ..InitMsg::default()
+        };
+        validate_init_msg(&valid).unwrap();
+
+        let invalid = vec![
+            InitMsg {
+                count: -1,
+                unsigned: true,
+                ..InitMsg::default()
+            },
+            InitMsg {
+                unsigned: true,
+                clamp_on_overflow: true,
+                ..InitMsg::default()
+            },
+            InitMsg {
+                subscriber: Some(HumanAddr::from(" ")),
+                ..InitMsg::default()
+            },
+        ];
+        for msg in invalid {
+            match validate_init_msg(&msg) {
+                Err(StdError::GenericErr { .. }) => {}
+                _ => panic!("Must reject {:?}", msg),
+            }
+
+            // init runs the same checks
+            let mut deps = mock_dependencies(20, &[]);
+            let env = mock_env(&deps.api, "creator", &[]);
+            assert!(init(&mut deps, env, msg).is_err());
+        }
+    }
+
+    #[test]
+    fn auto_drift() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            auto_drift_per_block: 1,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // five blocks of drift land before the explicit increase
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 105;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 2 }).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 24", value);
+
+        // drift is only applied once per block
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 105;
+        let _res = handle(&mut deps, env, HandleMsg::Decrease { value: 1 }).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 23", value);
+    }
+
+    #[test]
+    fn frozen_blocks_do_not_drift() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            auto_drift_per_block: 1,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = handle(&mut deps, env, HandleMsg::FreezeCount {}).unwrap();
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 500;
+        let _res = handle(&mut deps, env, HandleMsg::UnfreezeCount {}).unwrap();
+
+        // only the one block since unfreezing drifts
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 501;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 0 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 18", value);
+    }
+
+    #[test]
+    fn streak_to_goal() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // two mutations in a row that end on an odd count
+        for _ in 0..2 {
+            let env = mock_env(&deps.api, "anyone", &[]);
+            let _res = handle(&mut deps, env, HandleMsg::Increase { value: 2 }).unwrap();
+        }
+
+        let res = query(&deps, QueryMsg::QueryStreakToGoal { goal: 5 }).unwrap();
+        let value: StreakToGoalResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.streak);
+        assert_eq!(3, value.remaining);
+
+        let res = query(&deps, QueryMsg::QueryStreakToGoal { goal: 2 }).unwrap();
+        let value: StreakToGoalResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.remaining);
+
+        // flipping the parity starts a new streak
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryStreakToGoal { goal: 5 }).unwrap();
+        let value: StreakToGoalResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.streak);
+        assert_eq!(4, value.remaining);
+    }
+
+    #[test]
+    fn freeze_count() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // only the owner can freeze
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::FreezeCount {});
+        match res {
+            Err(StdError::Unauthorized { .. }) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::FreezeCount {}).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 1 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!("count is frozen", msg),
+            _ => panic!("Must reject mutation while frozen"),
+        }
+        let env = mock_env(&deps.api, "creator", &[]);
+        assert!(handle(&mut deps, env, HandleMsg::Reset { count: 4 }).is_err());
+
+        // queries keep working on the frozen count
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 17", value);
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::UnfreezeCount {}).unwrap();
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 18", value);
+    }
+
+    #[test]
+    fn defaults() {
+        let deps = mock_dependencies(20, &[]);
+
+        let res = query(&deps, QueryMsg::QueryDefaults {}).unwrap();
+        let value: DefaultsResponse = from_binary(&res).unwrap();
+        let expected = InitMsg {
+            count: 0,
+            subscriber: None,
+            unsigned: false,
+            clamp_on_overflow: false,
+            reset_cooldown_blocks: 0,
+            auto_drift_per_block: 0,
+            notify_on_reset: None,
+            activation_delay_blocks: None,
+            safe_floor: None,
+            decrease_penalty: None,
+            deposit_denom: None,
+            reset_admins: vec![],
+            reset_threshold: 0,
+        };
+        assert_eq!(expected, value.init_msg);
+        assert_eq!(MAX_DRIFT_BLOCKS, value.max_drift_blocks);
+        assert_eq!(SCHEMA_VERSION, value.schema_version);
+    }
+
+    #[test]
+    fn probability_hint() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 16,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryProbabilityHint {}).unwrap();
+        let value: ProbabilityHintResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ProbabilityHintResponse {
+                even_pct: 50,
+                odd_pct: 50
+            },
+            value
+        );
+
+        // frozen on an even count
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::FreezeCount {}).unwrap();
+        let res = query(&deps, QueryMsg::QueryProbabilityHint {}).unwrap();
+        let value: ProbabilityHintResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ProbabilityHintResponse {
+                even_pct: 100,
+                odd_pct: 0
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn init_only_once() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let msg = InitMsg {
+            count: 4,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "intruder", &[]);
+        let res = init(&mut deps, env, msg);
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("contract is already initialized", msg)
+            }
+            _ => panic!("Must reject a second init"),
+        }
+
+        // the original state and owner survived
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 17", value);
+        let env = mock_env(&deps.api, "intruder", &[]);
+        assert!(handle(&mut deps, env, HandleMsg::Reset { count: 4 }).is_err());
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: 4 }).unwrap();
+    }
+
+    #[test]
+    fn normalize_addr() {
+        let deps = mock_dependencies(20, &[]);
+
+        let addr = HumanAddr::from("someone");
+        let res = query(&deps, QueryMsg::QueryNormalizeAddr { addr: addr.clone() }).unwrap();
+        let value: NormalizeAddrResponse = from_binary(&res).unwrap();
+        assert_eq!(deps.api.canonical_address(&addr).unwrap(), value.canonical);
+        assert_eq!(addr, value.human);
+
+        let addr = HumanAddr::from("this-address-is-too-long-for-the-mock");
+        let res = query(&deps, QueryMsg::QueryNormalizeAddr { addr });
+        match res {
+            Err(StdError::ParseErr { .. }) => {}
+            _ => panic!("Must return parse error"),
+        }
+    }
+
+    #[test]
+    fn audit_trail() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 2 }).unwrap();
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: 4 }).unwrap();
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::FreezeCount {}).unwrap();
+
+        let msg = QueryMsg::QueryAuditTrail {
+            start: None,
+            limit: None,
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        let events: Vec<AuditEvent> = value.events.iter().map(|e| e.event.clone()).collect();
+        assert_eq!(
+            vec![
+                AuditEvent::SetCountFrozen { frozen: true },
+                AuditEvent::Reset { count: 4 },
+                AuditEvent::Increase { value: 2 },
+            ],
+            events
+        );
+        assert_eq!(HumanAddr::from("anyone"), value.events[2].sender);
+        assert_eq!("19", value.events[2].count);
+
+        // second page of one
+        let msg = QueryMsg::QueryAuditTrail {
+            start: Some(1),
+            limit: Some(1),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.events.len());
+        assert_eq!(AuditEvent::Reset { count: 4 }, value.events[0].event);
+    }
+
+    #[test]
+    fn notify_on_reset() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            notify_on_reset: Some(HumanAddr::from("watcher")),
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Reset { count: 5 }).unwrap();
+        assert_eq!(vec![log("notify_on_reset", "watcher")], res.log);
+        assert_eq!(0, res.messages.len());
+
+        // increases never notify
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        assert_eq!(0, res.log.len());
+    }
+
+    #[test]
+    fn no_reset_notice_unless_configured() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Reset { count: 5 }).unwrap();
+        assert_eq!(0, res.log.len());
+    }
+
+    #[test]
+    fn parity_bit() {
+        for &(count, bit) in &[(16, 0u8), (17, 1), (-3, 1)] {
+            let mut deps = mock_dependencies(20, &[]);
+
+            let msg = InitMsg {
+                count,
+                ..InitMsg::default()
+            };
+            let env = mock_env(&deps.api, "creator", &[]);
+            let _res = init(&mut deps, env, msg).unwrap();
+
+            let res = query(&deps, QueryMsg::QueryParityBit {}).unwrap();
+            let value: ParityBitResponse = from_binary(&res).unwrap();
+            assert_eq!(bit, value.bit);
+        }
+    }
+
+    #[test]
+    fn effective_config() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            reset_cooldown_blocks: 10,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEffectiveConfig {}).unwrap();
+        let value: EffectiveConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(HumanAddr::from("creator")), value.owner);
+        assert_eq!(None, value.subscriber);
+        assert!(!value.unsigned);
+        // nothing set the bounds, the count type's range applies
+        assert_eq!("-2147483648", value.min_count);
+        assert_eq!("2147483647", value.max_count);
+        // never reset, so nothing to wait for
+        assert_eq!(0, value.next_reset_height);
+        assert_eq!(MAX_DRIFT_BLOCKS, value.max_drift_blocks);
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: 4 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEffectiveConfig {}).unwrap();
+        let value: EffectiveConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(110, value.next_reset_height);
+    }
+
+    #[test]
+    fn residue() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        for &(modulus, residue) in &[(3, 2), (5, 2), (2, 1)] {
+            let res = query(&deps, QueryMsg::QueryResidue { modulus }).unwrap();
+            let value: ResidueResponse = from_binary(&res).unwrap();
+            assert_eq!(residue, value.residue);
+        }
+
+        let res = query(&deps, QueryMsg::QueryResidue { modulus: 0 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!("modulus must be positive", msg),
+            _ => panic!("Must reject a zero modulus"),
+        }
+    }
+
+    #[test]
+    fn attestation() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryAttestation {}).unwrap();
+        let first: AttestationResponse = from_binary(&res).unwrap();
+        assert_eq!("17", first.count);
+        assert_eq!(Parity::Odd, first.parity);
+        assert_eq!(100, first.height);
+        assert_eq!(Sha256::digest(b"17|odd|100").to_vec(), first.hash.0);
+
+        // stable while nothing changes
+        let res = query(&deps, QueryMsg::QueryAttestation {}).unwrap();
+        let again: AttestationResponse = from_binary(&res).unwrap();
+        assert_eq!(first, again);
+
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 100;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryAttestation {}).unwrap();
+        let changed: AttestationResponse = from_binary(&res).unwrap();
+        assert_ne!(first.hash, changed.hash);
+    }
+
+    #[test]
+    fn query_before_init() {
+        let deps = mock_dependencies(20, &[]);
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {});
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!("contract not initialized", msg),
+            _ => panic!("Must return not initialized error"),
+        }
+    }
+
+    #[test]
+    fn adjust_by_percent() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 1_000_000,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // +10%
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::AdjustByPercent { bps: 1_000 };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 1100000", value);
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::AdjustByPercent { bps: i32::MAX };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::GenericErr { .. }) => {}
+            _ => panic!("Must return overflow error"),
+        }
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 1100000", value);
+    }
+
+    #[test]
+    fn extremes() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryExtremes {}).unwrap();
+        let value: ExtremesResponse = from_binary(&res).unwrap();
+        assert_eq!("17", value.max);
+        assert_eq!("17", value.min);
+
+        for msg in &[
+            HandleMsg::Increase { value: 5 },
+            HandleMsg::Decrease { value: 30 },
+            HandleMsg::Increase { value: 10 },
+        ] {
+            let env = mock_env(&deps.api, "anyone", &[]);
+            let _res = handle(&mut deps, env, msg.clone()).unwrap();
+        }
+
+        // went 17 -> 22 -> -8 -> 2
+        let res = query(&deps, QueryMsg::QueryExtremes {}).unwrap();
+        let value: ExtremesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ExtremesResponse {
+                max: "22".to_string(),
+                max_parity: Parity::Even,
+                min: "-8".to_string(),
+                min_parity: Parity::Even,
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn flip_parity() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::FlipParity {}).unwrap();
+        assert_eq!(vec![log("action", "flip")], res.log);
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 18", value);
+
+        // at the top of the range the flip goes down instead
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::Reset { count: i32::MAX };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::FlipParity {}).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!(format!("Even Number: {}", i32::MAX - 1), value);
+    }
+
+    #[test]
+    fn activation_delay() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            activation_delay_blocks: Some(5),
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 102;
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 1 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                "contract is not yet active, mutations allowed from block 105",
+                msg
+            ),
+            _ => panic!("Must reject mutation before activation"),
+        }
+
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 106;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 18", value);
+    }
+
+    #[test]
+    fn donate() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "sponsor", &coins(100, "uscrt"));
+        let res = handle(&mut deps, env, HandleMsg::Donate {}).unwrap();
+        assert_eq!(
+            vec![log("donated", "100uscrt"), log("donor", "sponsor")],
+            res.log
+        );
+        let env = mock_env(&deps.api, "sponsor", &coins(50, "uscrt"));
+        let _res = handle(&mut deps, env, HandleMsg::Donate {}).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryTreasury {}).unwrap();
+        let value: TreasuryResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(150, "uscrt"), value.treasury);
+
+        let env = mock_env(&deps.api, "sponsor", &coins(0, "uscrt"));
+        let res = handle(&mut deps, env, HandleMsg::Donate {});
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!("no funds sent to donate", msg),
+            _ => panic!("Must reject an empty donation"),
+        }
+    }
+
+    #[test]
+    fn participant_count() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let participants = |deps: &Extern<_, _, _>| {
+            let res = query(deps, QueryMsg::QueryParticipantCount {}).unwrap();
+            let value: ParticipantCountResponse = from_binary(&res).unwrap();
+            value.unique_participants
+        };
+        assert_eq!(0, participants(&deps));
+
+        for _ in 0..2 {
+            let env = mock_env(&deps.api, "anyone", &[]);
+            let msg = HandleMsg::Increase { value: 1 };
+            let _res = handle(&mut deps, env, msg).unwrap();
+        }
+        assert_eq!(1, participants(&deps));
+
+        let env = mock_env(&deps.api, "someone", &[]);
+        let msg = HandleMsg::Decrease { value: 1 };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        assert_eq!(2, participants(&deps));
+    }
+
+    #[test]
+    fn scheduled_reset() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::ScheduleReset {
+            count: 4,
+            at_height: 50,
+        };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::Unauthorized { .. }) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 10;
+        let msg = HandleMsg::ScheduleReset {
+            count: 4,
+            at_height: 50,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        // not due yet, the schedule stays pending
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 40;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 18", value);
+
+        // due, the reset to 4 is applied before the increase
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 60;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 5", value);
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 70;
+        let msg = HandleMsg::ScheduleReset {
+            count: 0,
+            at_height: 100,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 80;
+        let _res = handle(&mut deps, env, HandleMsg::CancelScheduledReset {}).unwrap();
+
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 120;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 6", value);
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::CancelScheduledReset {});
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!("no reset is scheduled", msg),
+            _ => panic!("Must reject cancelling without a schedule"),
+        }
+    }
+
+    #[test]
+    fn applied_scheduled_reset_is_recorded() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            notify_on_reset: Some(HumanAddr::from("watcher")),
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 10;
+        let msg = HandleMsg::ScheduleReset {
+            count: -40,
+            at_height: 50,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        // once due it can no longer be replaced or cancelled
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 60;
+        let msg = HandleMsg::ScheduleReset {
+            count: 0,
+            at_height: 70,
+        };
+        assert!(handle(&mut deps, env, msg).is_err());
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 60;
+        assert!(handle(&mut deps, env, HandleMsg::CancelScheduledReset {}).is_err());
+
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 61;
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        assert!(res.log.contains(&log("notify_on_reset", "watcher")));
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: -39", value);
+        let res = query(&deps, QueryMsg::QueryExtremes {}).unwrap();
+        let value: ExtremesResponse = from_binary(&res).unwrap();
+        assert_eq!("-40", value.min);
+
+        let msg = QueryMsg::QueryAuditTrail {
+            start: None,
+            limit: Some(2),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        let events: Vec<(AuditEvent, String)> = value
+            .events
+            .into_iter()
+            .map(|e| (e.event, e.count))
+            .collect();
+        assert_eq!(
+            vec![
+                (AuditEvent::Increase { value: 1 }, "-39".to_string()),
+                (AuditEvent::Reset { count: -40 }, "-40".to_string()),
+            ],
+            events
+        );
+        let msg = QueryMsg::QueryOwnerActions {
+            start: None,
+            limit: Some(1),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: OwnerActionsResponse = from_binary(&res).unwrap();
+        assert_eq!(AuditEvent::Reset { count: -40 }, value.actions[0].action);
+    }
+
+    #[test]
+    fn scheduled_reset_respects_cooldown() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            reset_cooldown_blocks: 100,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 100;
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: 0 }).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 101;
+        let msg = HandleMsg::ScheduleReset {
+            count: 4,
+            at_height: 102,
+        };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("reset is on cooldown, next reset allowed at block 200", msg)
+            }
+            _ => panic!("Must reject a scheduled reset inside the cooldown"),
+        }
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 101;
+        let msg = HandleMsg::ScheduleReset {
+            count: 4,
+            at_height: 250,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        // an explicit reset would put the scheduled one inside its cooldown
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 200;
+        assert!(handle(&mut deps, env, HandleMsg::Reset { count: 1 }).is_err());
+    }
+
+    #[test]
+    fn due_scheduled_reset_starts_cooldown() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            reset_cooldown_blocks: 50,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 10;
+        let msg = HandleMsg::ScheduleReset {
+            count: 4,
+            at_height: 100,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        // the reset due at 100 applies first, so 105 is inside its cooldown
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 105;
+        let res = handle(&mut deps, env, HandleMsg::Reset { count: 6 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("reset is on cooldown, next reset allowed at block 150", msg)
+            }
+            _ => panic!("Must return cooldown error"),
+        }
+
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 105;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+
+        let msg = QueryMsg::QueryAuditTrail {
+            start: None,
+            limit: Some(2),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        let events: Vec<(u64, AuditEvent)> = value
+            .events
+            .into_iter()
+            .map(|e| (e.height, e.event))
+            .collect();
+        assert_eq!(
+            vec![
+                (105, AuditEvent::Increase { value: 1 }),
+                (100, AuditEvent::Reset { count: 4 }),
+            ],
+            events
+        );
+        let msg = QueryMsg::QueryOwnerActions {
+            start: None,
+            limit: Some(1),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: OwnerActionsResponse = from_binary(&res).unwrap();
+        assert_eq!(100, value.actions[0].height);
+    }
+
+    #[test]
+    fn classify() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        for &(count, parity, is_prime, is_perfect_square) in &[
+            (17, Parity::Odd, true, false),
+            (16, Parity::Even, false, true),
+            (1, Parity::Odd, false, true),
+            (-7, Parity::Odd, false, false),
+        ] {
+            let env = mock_env(&deps.api, "creator", &[]);
+            let _res = handle(&mut deps, env, HandleMsg::Reset { count }).unwrap();
+
+            let res = query(&deps, QueryMsg::QueryClassify {}).unwrap();
+            let value: ClassifyResponse = from_binary(&res).unwrap();
+            assert_eq!(
+                ClassifyResponse {
+                    parity,
+                    is_prime,
+                    is_perfect_square,
+                },
+                value
+            );
+        }
+    }
+
+    // the serde tag of a message, the Debug name with rename_all = "snake_case" applied
+    fn tag<T: std::fmt::Debug>(msg: &T) -> String {
+        let mut tag = String::new();
+        for (i, c) in format!("{:?}", msg)
+            .chars()
+            .take_while(|c| c.is_alphanumeric())
+            .enumerate()
+        {
+            if c.is_uppercase() && i > 0 {
+                tag.push('_');
+            }
+            tag.extend(c.to_lowercase());
+        }
+        tag
+    }
+
+    // one value of every HandleMsg variant in list order, the match has no catch-all arm so a
+    // new variant does not build until it gets a sample here
+    fn handle_samples() -> Vec<HandleMsg> {
+        let samples = vec![
+            HandleMsg::Increase { value: 0 },
+            HandleMsg::Decrease { value: 0 },
+            HandleMsg::Reset { count: 0 },
+            HandleMsg::FreezeCount {},
+            HandleMsg::UnfreezeCount {},
+            HandleMsg::AdjustByPercent { bps: 0 },
+            HandleMsg::FlipParity {},
+            HandleMsg::Donate {},
+            HandleMsg::ScheduleReset {
+                count: 0,
+                at_height: 0,
+            },
+            HandleMsg::CancelScheduledReset {},
+            HandleMsg::IncreaseIfParity {
+                value: 0,
+                expected: Parity::Even,
+            },
+            HandleMsg::RenounceOwnership { confirm: false },
+            HandleMsg::ImportState {
+                count: 0,
+                history: vec![],
+            },
+            HandleMsg::PruneHistory { keep_last: 0 },
+            HandleMsg::SetActiveWindows { windows: vec![] },
+            HandleMsg::SetGlyphs {
+                even: String::new(),
+                odd: String::new(),
+            },
+            HandleMsg::IncreaseByDeposit {},
+            HandleMsg::SetAlias {
+                alias: String::new(),
+            },
+            HandleMsg::ProposeReset { count: 0 },
+            HandleMsg::ApproveReset { proposal_id: 0 },
+        ];
+        for msg in &samples {
+            match msg {
+                HandleMsg::Increase { .. }
+                | HandleMsg::Decrease { .. }
+                | HandleMsg::Reset { .. }
+                | HandleMsg::FreezeCount { .. }
+                | HandleMsg::UnfreezeCount { .. }
+                | HandleMsg::AdjustByPercent { .. }
+                | HandleMsg::FlipParity { .. }
+                | HandleMsg::Donate { .. }
+                | HandleMsg::ScheduleReset { .. }
+                | HandleMsg::CancelScheduledReset { .. }
+                | HandleMsg::IncreaseIfParity { .. }
+                | HandleMsg::RenounceOwnership { .. }
+                | HandleMsg::ImportState { .. }
+                | HandleMsg::PruneHistory { .. }
+                | HandleMsg::SetActiveWindows { .. }
+                | HandleMsg::SetGlyphs { .. }
+                | HandleMsg::IncreaseByDeposit { .. }
+                | HandleMsg::SetAlias { .. }
+                | HandleMsg::ProposeReset { .. }
+                | HandleMsg::ApproveReset { .. } => {}
+            }
+        }
+        samples
+    }
+
+    // one value of every QueryMsg variant in list order, the match has no catch-all arm so a
+    // new variant does not build until it gets a sample here
+    fn query_samples() -> Vec<QueryMsg> {
+        let samples = vec![
+            QueryMsg::QueryEvenOdd {},
+            QueryMsg::QueryInfo {},
+            QueryMsg::QueryStreakToGoal { goal: 0 },
+            QueryMsg::QueryDefaults {},
+            QueryMsg::QueryProbabilityHint {},
+            QueryMsg::QueryNormalizeAddr {
+                addr: HumanAddr::from("anyone"),
+            },
+            QueryMsg::QueryAuditTrail {
+                start: None,
+                limit: None,
+            },
+            QueryMsg::QueryParityBit {},
+            QueryMsg::QueryEffectiveConfig {},
+            QueryMsg::QueryResidue { modulus: 0 },
+            QueryMsg::QueryAttestation {},
+            QueryMsg::QueryExtremes {},
+            QueryMsg::QueryTreasury {},
+            QueryMsg::QueryParticipantCount {},
+            QueryMsg::QueryClassify {},
+            QueryMsg::QuerySupportedMessages {},
+            QueryMsg::QueryBoundaryDistance {},
+            QueryMsg::QueryParityDwell {},
+            QueryMsg::QueryDriftForecast { blocks: 0 },
+            QueryMsg::QueryOwnerActions {
+                start: None,
+                limit: None,
+            },
+            QueryMsg::QueryPredicate {
+                min: None,
+                max: None,
+                parity: None,
+                divisible_by: None,
+            },
+            QueryMsg::QueryStorageStats {},
+            QueryMsg::QueryLuckyNumber {
+                address: HumanAddr::from("anyone"),
+            },
+            QueryMsg::QueryMutationStatus { height: 0 },
+            QueryMsg::QueryLowDigit {},
+            QueryMsg::QueryGlyph {},
+            QueryMsg::QueryParityIfChanged { since_count: 0 },
+            QueryMsg::QueryAlias {
+                address: HumanAddr::from("anyone"),
+            },
+            QueryMsg::QueryScheduledReset { height: 0 },
+            QueryMsg::QueryResetProposal { proposal_id: 0 },
+        ];
+        for msg in &samples {
+            match msg {
+                QueryMsg::QueryEvenOdd { .. }
+                | QueryMsg::QueryInfo { .. }
+                | QueryMsg::QueryStreakToGoal { .. }
+                | QueryMsg::QueryDefaults { .. }
+                | QueryMsg::QueryProbabilityHint { .. }
+                | QueryMsg::QueryNormalizeAddr { .. }
+                | QueryMsg::QueryAuditTrail { .. }
+                | QueryMsg::QueryParityBit { .. }
+                | QueryMsg::QueryEffectiveConfig { .. }
+                | QueryMsg::QueryResidue { .. }
+                | QueryMsg::QueryAttestation { .. }
+                | QueryMsg::QueryExtremes { .. }
+                | QueryMsg::QueryTreasury { .. }
+                | QueryMsg::QueryParticipantCount { .. }
+                | QueryMsg::QueryClassify { .. }
+                | QueryMsg::QuerySupportedMessages { .. }
+                | QueryMsg::QueryBoundaryDistance { .. }
+                | QueryMsg::QueryParityDwell { .. }
+                | QueryMsg::QueryDriftForecast { .. }
+                | QueryMsg::QueryOwnerActions { .. }
+                | QueryMsg::QueryPredicate { .. }
+                | QueryMsg::QueryStorageStats { .. }
+                | QueryMsg::QueryLuckyNumber { .. }
+                | QueryMsg::QueryMutationStatus { .. }
+                | QueryMsg::QueryLowDigit { .. }
+                | QueryMsg::QueryGlyph { .. }
+                | QueryMsg::QueryParityIfChanged { .. }
+                | QueryMsg::QueryAlias { .. }
+                | QueryMsg::QueryScheduledReset { .. }
+                | QueryMsg::QueryResetProposal { .. } => {}
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn message_lists_are_complete() {
+        let tags: Vec<String> = handle_samples().iter().map(tag).collect();
+        assert_eq!(HANDLE_MESSAGES.to_vec(), tags);
+        let tags: Vec<String> = query_samples().iter().map(tag).collect();
+        assert_eq!(QUERY_MESSAGES.to_vec(), tags);
+    }
+
+    #[test]
+    fn supported_messages() {
+        let deps = mock_dependencies(20, &[]);
+
+        let res = query(&deps, QueryMsg::QuerySupportedMessages {}).unwrap();
+        let value: SupportedMessagesResponse = from_binary(&res).unwrap();
+        for name in &["increase", "decrease", "reset"] {
+            assert!(value.handle.contains(&name.to_string()), "missing {}", name);
+        }
+        assert!(value.query.contains(&"query_even_odd".to_string()));
+        assert!(value
+            .query
+            .contains(&"query_supported_messages".to_string()));
+    }
+
+    #[test]
+    fn boundary_distance() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryBoundaryDistance {}).unwrap();
+        let value: BoundaryDistanceResponse = from_binary(&res).unwrap();
+        assert_eq!("2147483630", value.to_max);
+        assert_eq!("2147483665", value.to_min);
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::Reset { count: i32::MIN };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        let res = query(&deps, QueryMsg::QueryBoundaryDistance {}).unwrap();
+        let value: BoundaryDistanceResponse = from_binary(&res).unwrap();
+        assert_eq!("4294967295", value.to_max);
+        assert_eq!("0", value.to_min);
+    }
+
+    #[test]
+    fn parity_dwell() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // odd from 1, even from 10, odd from 15, even from 21
+        for &height in &[10, 15, 21] {
+            let mut env = mock_env(&deps.api, "anyone", &[]);
+            env.block.height = height;
+            let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        }
+        // same parity, nothing is accumulated
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 30;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 2 }).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryParityDwell {}).unwrap();
+        let value: ParityDwellResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ParityDwellResponse {
+                even_blocks: 5,
+                odd_blocks: 15,
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn drift_forecast() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            auto_drift_per_block: 1,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryDriftForecast { blocks: 3 }).unwrap();
+        let value: DriftForecastResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            DriftForecastResponse {
+                count: "20".to_string(),
+                parity: Parity::Even,
+            },
+            value
+        );
+
+        // capped like a real handler call
+        let msg = QueryMsg::QueryDriftForecast { blocks: u64::MAX };
+        let res = query(&deps, msg).unwrap();
+        let value: DriftForecastResponse = from_binary(&res).unwrap();
+        assert_eq!((17 + MAX_DRIFT_BLOCKS).to_string(), value.count);
+    }
+
+    #[test]
+    fn owner_actions() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 10;
+        let msg = HandleMsg::ScheduleReset {
+            count: 0,
+            at_height: 50,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 11;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 12;
+        let _res = handle(&mut deps, env, HandleMsg::FreezeCount {}).unwrap();
+
+        // gameplay is left out
+        let msg = QueryMsg::QueryOwnerActions {
+            start: None,
+            limit: None,
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: OwnerActionsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![
+                OwnerAction {
+                    height: 12,
+                    action: AuditEvent::SetCountFrozen { frozen: true },
+                },
+                OwnerAction {
+                    height: 10,
+                    action: AuditEvent::ScheduleReset {
+                        count: 0,
+                        at_height: 50,
+                    },
+                },
+            ],
+            value.actions
+        );
+    }
+
+    #[test]
+    fn increase_if_parity() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::IncreaseIfParity {
+            value: 2,
+            expected: Parity::Even,
+        };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!("count is odd, expected even", msg),
+            _ => panic!("Must reject a parity mismatch"),
+        }
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 17", value);
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::IncreaseIfParity {
+            value: 2,
+            expected: Parity::Odd,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 19", value);
+    }
+
+    #[test]
+    fn predicate() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 18,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let matches = |min, max, parity, divisible_by| {
+            let msg = QueryMsg::QueryPredicate {
+                min,
+                max,
+                parity,
+                divisible_by,
+            };
+            let res = query(&deps, msg).unwrap();
+            let value: PredicateResponse = from_binary(&res).unwrap();
+            value.matches
+        };
+        assert!(matches(None, None, None, None));
+        assert!(matches(None, None, Some(Parity::Even), Some(3)));
+        assert!(matches(Some(18), Some(18), Some(Parity::Even), Some(-9)));
+        assert!(!matches(Some(20), None, Some(Parity::Even), Some(3)));
+        assert!(!matches(None, None, Some(Parity::Odd), None));
+        assert!(!matches(None, None, None, Some(4)));
+
+        let msg = QueryMsg::QueryPredicate {
+            min: None,
+            max: None,
+            parity: None,
+            divisible_by: Some(0),
+        };
+        match query(&deps, msg) {
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("divisible_by must not be zero", msg)
+            }
+            _ => panic!("Must reject a zero divisor"),
+        }
+    }
+
+    #[test]
+    fn renounce_ownership() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::RenounceOwnership { confirm: false };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::GenericErr { .. }) => {}
+            _ => panic!("Must require confirmation"),
+        }
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::RenounceOwnership { confirm: true };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Reset { count: 5 });
+        match res {
+            Err(StdError::Unauthorized { .. }) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let res = query(&deps, QueryMsg::QueryEffectiveConfig {}).unwrap();
+        let value: EffectiveConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.owner);
+
+        // the game itself keeps going
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+    }
+
+    #[test]
+    fn renounce_ownership_cancels_scheduled_reset() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 10;
+        let msg = HandleMsg::ScheduleReset {
+            count: 4,
+            at_height: 50,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 20;
+        let msg = HandleMsg::RenounceOwnership { confirm: true };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryScheduledReset { height: 20 }).unwrap();
+        let value: ScheduledResetResponse = from_binary(&res).unwrap();
+        assert!(!value.pending);
+
+        // the reset the former owner scheduled never fires
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 60;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 18", value);
+    }
+
+    #[test]
+    fn import_state() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 0,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let history = vec![
+            AuditEntry {
+                height: 7,
+                sender: HumanAddr::from("player"),
+                event: AuditEvent::Increase { value: 40 },
+                count: "40".to_string(),
+            },
+            AuditEntry {
+                height: 9,
+                sender: HumanAddr::from("player"),
+                event: AuditEvent::Increase { value: 2 },
+                count: "42".to_string(),
+            },
+        ];
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::ImportState {
+            count: 42,
+            history: history.clone(),
+        };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::Unauthorized { .. }) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::ImportState {
+            count: 42,
+            history: history.clone(),
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 42", value);
+        let msg = QueryMsg::QueryAuditTrail {
+            start: Some(1),
+            limit: None,
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![history[1].clone(), history[0].clone()], value.events);
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::ImportState {
+            count: 1,
+            history: vec![],
+        };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!("state was already imported", msg),
+            _ => panic!("Must reject a second import"),
+        }
+    }
+
+    #[test]
+    fn import_state_bookkeeping() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            subscriber: Some(HumanAddr::from("dashboard")),
+            auto_drift_per_block: 1,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // strangers writing to the audit trail do not close the import
+        let mut env = mock_env(&deps.api, "stranger", &[]);
+        env.block.height = 5;
+        let msg = HandleMsg::SetAlias {
+            alias: "first".to_string(),
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        let mut env = mock_env(&deps.api, "stranger", &coins(1, "uscrt"));
+        env.block.height = 5;
+        let _res = handle(&mut deps, env, HandleMsg::Donate {}).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 10;
+        let msg = HandleMsg::ImportState {
+            count: 42,
+            history: vec![],
+        };
+        let res = handle(&mut deps, env, msg).unwrap();
+        let expected = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: HumanAddr::from("dashboard"),
+            msg: to_binary(&SubscriberMsg::ParityChanged {
+                new_parity: Parity::Even,
+                count: "42".to_string(),
+            })
+            .unwrap(),
+            send: vec![],
+        });
+        assert_eq!(vec![expected], res.messages);
+
+        // drift restarts from the import, the blocks before it went into the old count
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 15;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 0 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 47", value);
+
+        // a count change closes the import on a fresh game
+        let mut deps = mock_dependencies(20, &[]);
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, InitMsg::default()).unwrap();
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::ImportState {
+            count: 42,
+            history: vec![],
+        };
+        assert!(handle(&mut deps, env, msg).is_err());
+    }
+
+    #[test]
+    fn storage_stats() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        for sender in &["anyone", "someone", "anyone"] {
+            let env = mock_env(&deps.api, *sender, &[]);
+            let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        }
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::FreezeCount {}).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryStorageStats {}).unwrap();
+        let value: StorageStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            StorageStatsResponse {
+                audit_entries: 4,
+                owner_actions: 1,
+                participants: 2,
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn prune_history() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 0,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        for _ in 0..10 {
+            let env = mock_env(&deps.api, "anyone", &[]);
+            let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        }
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::PruneHistory { keep_last: 3 });
+        match res {
+            Err(StdError::Unauthorized { .. }) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::PruneHistory { keep_last: 3 }).unwrap();
+        assert_eq!(vec![log("pruned", "7")], res.log);
+
+        let msg = QueryMsg::QueryAuditTrail {
+            start: None,
+            limit: Some(MAX_PAGE_LIMIT),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        let counts: Vec<&str> = value.events.iter().map(|e| e.count.as_str()).collect();
+        assert_eq!(vec!["10", "9", "8"], counts);
+
+        // a page past the retained entries is empty
+        let msg = QueryMsg::QueryAuditTrail {
+            start: Some(3),
+            limit: None,
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        assert!(value.events.is_empty());
+
+        // nothing left to prune
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::PruneHistory { keep_last: 5 }).unwrap();
+        assert_eq!(vec![log("pruned", "0")], res.log);
+    }
+
+    #[test]
+    fn lucky_number() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 0,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let lucky = |deps: &Extern<_, _, _>| {
+            let msg = QueryMsg::QueryLuckyNumber {
+                address: HumanAddr::from("player"),
+            };
+            let res = query(deps, msg).unwrap();
+            let value: LuckyNumberResponse = from_binary(&res).unwrap();
+            assert!((1..=100).contains(&value.number));
+            assert_eq!(Parity::of(i32::from(value.number)), value.parity);
+            value.number
+        };
+        assert_eq!(lucky(&deps), lucky(&deps));
+
+        let mut numbers = vec![];
+        for _ in 0..10 {
+            numbers.push(lucky(&deps));
+            let env = mock_env(&deps.api, "anyone", &[]);
+            let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        }
+        numbers.dedup();
+        assert!(numbers.len() > 1);
+    }
+
+    #[test]
+    fn decrease_penalty() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            safe_floor: Some(10),
+            decrease_penalty: Some(Coin {
+                denom: "uscrt".to_string(),
+                amount: Uint128(100),
+            }),
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // 17 -> 10 stays on the floor, no fee
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Decrease { value: 7 }).unwrap();
+        assert!(res.log.is_empty());
+
+        // 10 -> 7 is three units below
+        let env = mock_env(&deps.api, "anyone", &coins(299, "uscrt"));
+        let res = handle(&mut deps, env, HandleMsg::Decrease { value: 3 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                "decreasing below the safe floor requires a fee of 300uscrt",
+                msg
+            ),
+            _ => panic!("Must require the penalty"),
+        }
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 10", value);
+
+        let mut funds = coins(300, "uscrt");
+        funds.push(Coin {
+            denom: "ufoo".to_string(),
+            amount: Uint128(0),
+        });
+        let env = mock_env(&deps.api, "anyone", &funds);
+        let res = handle(&mut deps, env, HandleMsg::Decrease { value: 3 }).unwrap();
+        assert_eq!(vec![log("penalty", "300uscrt")], res.log);
+        let res = query(&deps, QueryMsg::QueryTreasury {}).unwrap();
+        let value: TreasuryResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(300, "uscrt"), value.treasury);
+
+        // the floor holds against any handler lowering the count, 7 -> 5
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: -2 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                "decreasing below the safe floor requires a fee of 200uscrt",
+                msg
+            ),
+            _ => panic!("Must require the penalty"),
+        }
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::AdjustByPercent { bps: -5000 };
+        assert!(handle(&mut deps, env, msg).is_err());
+
+        let env = mock_env(&deps.api, "anyone", &coins(200, "uscrt"));
+        let res = handle(&mut deps, env, HandleMsg::Increase { value: -2 }).unwrap();
+        assert!(res.log.contains(&log("penalty", "200uscrt")));
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 5", value);
+
+        // an owner reset below the floor is free
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: 0 }).unwrap();
+
+        let msg = InitMsg {
+            safe_floor: Some(10),
+            ..InitMsg::default()
+        };
+        assert!(validate_init_msg(&msg).is_err());
+    }
+
+    #[test]
+    fn active_windows() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 2;
+        let msg = HandleMsg::SetActiveWindows {
+            windows: vec![(20, 10)],
+        };
+        assert!(handle(&mut deps, env, msg).is_err());
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 2;
+        let msg = HandleMsg::SetActiveWindows {
+            windows: vec![(10, 20)],
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        for &(height, allowed) in &[(5, false), (10, true), (15, true), (20, true), (25, false)] {
+            let mut env = mock_env(&deps.api, "anyone", &[]);
+            env.block.height = height;
+            let res = handle(&mut deps, env, HandleMsg::Increase { value: 1 });
+            match res {
+                Ok(_) => assert!(allowed, "changed at {}", height),
+                Err(StdError::GenericErr { msg, .. }) => {
+                    assert!(!allowed, "rejected at {}", height);
+                    assert_eq!("the count can only change inside an active window", msg);
+                }
+                Err(err) => panic!("unexpected error {:?}", err),
+            }
+        }
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 20", value);
+    }
+
+    #[test]
+    fn mutation_status() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            activation_delay_blocks: Some(10),
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let status = |deps: &Extern<_, _, _>, height| {
+            let res = query(deps, QueryMsg::QueryMutationStatus { height }).unwrap();
+            let value: MutationStatusResponse = from_binary(&res).unwrap();
+            value
+        };
+        assert_eq!(
+            MutationStatusResponse {
+                allowed: false,
+                reason: Some(
+                    "contract is not yet active, mutations allowed from block 11".to_string()
+                ),
+            },
+            status(&deps, 5)
+        );
+        assert_eq!(
+            MutationStatusResponse {
+                allowed: true,
+                reason: None,
+            },
+            status(&deps, 11)
+        );
+
+        // frozen is reported first
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::FreezeCount {}).unwrap();
+        assert_eq!(Some("count is frozen".to_string()), status(&deps, 5).reason);
+    }
+
+    #[test]
+    fn low_digit() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let res = query(&deps, QueryMsg::QueryLowDigit {}).unwrap();
+        let value: LowDigitResponse = from_binary(&res).unwrap();
+        assert_eq!(7, value.digit);
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Reset { count: -13 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryLowDigit {}).unwrap();
+        let value: LowDigitResponse = from_binary(&res).unwrap();
+        assert_eq!(7, value.digit);
+    }
+
+    #[test]
+    fn glyphs() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let glyph = |deps: &Extern<_, _, _>| {
+            let res = query(deps, QueryMsg::QueryGlyph {}).unwrap();
+            let value: GlyphResponse = from_binary(&res).unwrap();
+            value.glyph
+        };
+        assert_eq!("🟧 odd", glyph(&deps));
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::SetGlyphs {
+            even: "E".to_string(),
+            odd: "odd".repeat(6),
+        };
+        assert!(handle(&mut deps, env, msg).is_err());
+
+        let env = mock_env(&deps.api, "creator", &[]);
+        let msg = HandleMsg::SetGlyphs {
+            even: "⚪".to_string(),
+            odd: "⚫".to_string(),
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+        assert_eq!("⚫", glyph(&deps));
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        assert_eq!("⚪", glyph(&deps));
+    }
+
+    #[test]
+    fn increase_by_deposit() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            deposit_denom: Some("uscrt".to_string()),
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut funds = coins(5, "uscrt");
+        funds.extend(coins(7, "uatom"));
+        let env = mock_env(&deps.api, "anyone", &funds);
+        let res = handle(&mut deps, env, HandleMsg::IncreaseByDeposit {}).unwrap();
+        assert!(res.log.contains(&log("deposited", "5uscrt")));
+        let msg = QueryMsg::QueryAuditTrail {
+            start: None,
+            limit: Some(1),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            AuditEvent::IncreaseByDeposit {
+                amount: coins(5, "uscrt").remove(0),
+            },
+            value.events[0].event
+        );
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 22", value);
+
+        // other denoms alone do not count
+        let env = mock_env(&deps.api, "anyone", &coins(5, "uatom"));
+        assert!(handle(&mut deps, env, HandleMsg::IncreaseByDeposit {}).is_err());
+
+        let too_much = i32::MAX as u128 + 1;
+        let env = mock_env(&deps.api, "anyone", &coins(too_much, "uscrt"));
+        let res = handle(&mut deps, env, HandleMsg::IncreaseByDeposit {});
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("deposit exceeds the range of the count", msg)
+            }
+            _ => panic!("Must reject a deposit outside the i32 range"),
+        }
+
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 22", value);
+        let res = query(&deps, QueryMsg::QueryTreasury {}).unwrap();
+        let value: TreasuryResponse = from_binary(&res).unwrap();
+        let mut expected = coins(5, "uscrt");
+        expected.extend(coins(7, "uatom"));
+        assert_eq!(expected, value.treasury);
+    }
+
+    #[test]
+    fn parity_if_changed() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let msg = QueryMsg::QueryParityIfChanged { since_count: 17 };
+        let res = query(&deps, msg).unwrap();
+        let value: ParityIfChangedResponse = from_binary(&res).unwrap();
+        assert!(!value.changed);
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 1 }).unwrap();
+        let msg = QueryMsg::QueryParityIfChanged { since_count: 17 };
+        let res = query(&deps, msg).unwrap();
+        let value: ParityIfChangedResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ParityIfChangedResponse {
+                changed: true,
+                count: "18".to_string(),
+                parity: Parity::Even,
+            },
+            value
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let alias_of = |deps: &Extern<_, _, _>, address: &str| {
+            let msg = QueryMsg::QueryAlias {
+                address: HumanAddr::from(address),
+            };
+            let res = query(deps, msg).unwrap();
+            let value: AliasResponse = from_binary(&res).unwrap();
+            value.alias
+        };
+        assert_eq!(None, alias_of(&deps, "anyone"));
+
+        let env = mock_env(&deps.api, "anyone", &[]);
+        let msg = HandleMsg::SetAlias {
+            alias: "x".repeat(MAX_ALIAS_LEN + 1),
+        };
+        assert!(handle(&mut deps, env, msg).is_err());
+
+        for alias in &["first", "second"] {
+            let env = mock_env(&deps.api, "anyone", &[]);
+            let msg = HandleMsg::SetAlias {
+                alias: alias.to_string(),
+            };
+            let _res = handle(&mut deps, env, msg).unwrap();
+            assert_eq!(Some(alias.to_string()), alias_of(&deps, "anyone"));
+        }
+        let msg = QueryMsg::QueryAuditTrail {
+            start: None,
+            limit: Some(1),
+        };
+        let res = query(&deps, msg).unwrap();
+        let value: AuditTrailResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            AuditEvent::SetAlias {
+                alias: "second".to_string(),
+            },
+            value.events[0].event
+        );
+        assert_eq!(None, alias_of(&deps, "creator"));
+    }
+
+    #[test]
+    fn scheduled_reset_countdown() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let msg = QueryMsg::QueryScheduledReset { height: 90 };
+        let res = query(&deps, msg).unwrap();
+        let value: ScheduledResetResponse = from_binary(&res).unwrap();
+        assert!(!value.pending);
+        assert_eq!(None, value.blocks_remaining);
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 50;
+        let msg = HandleMsg::ScheduleReset {
+            count: 4,
+            at_height: 100,
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        let msg = QueryMsg::QueryScheduledReset { height: 90 };
+        let res = query(&deps, msg).unwrap();
+        let value: ScheduledResetResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ScheduledResetResponse {
+                pending: true,
+                at_height: Some(100),
+                blocks_remaining: Some(10),
+                new_count: Some(4),
+            },
+            value
+        );
+
+        let msg = QueryMsg::QueryScheduledReset { height: 120 };
+        let res = query(&deps, msg).unwrap();
+        let value: ScheduledResetResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(0), value.blocks_remaining);
+    }
+
+    #[test]
+    fn closed_windows_do_not_drift() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            auto_drift_per_block: 1,
+            ..InitMsg::default()
+        };
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        let mut env = mock_env(&deps.api, "creator", &[]);
+        env.block.height = 1;
+        let msg = HandleMsg::SetActiveWindows {
+            windows: vec![(50, 59), (10, 19), (15, 25)],
+        };
+        let _res = handle(&mut deps, env, msg).unwrap();
+
+        // blocks 10-25 and 50-55 drift, overlapping windows count once
+        let mut env = mock_env(&deps.api, "anyone", &[]);
+        env.block.height = 55;
+        let _res = handle(&mut deps, env, HandleMsg::Increase { value: 0 }).unwrap();
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 39", value);
+    }
+
+    #[test]
+    fn reset_needs_admin_approvals() {
+        let mut deps = mock_dependencies(20, &[]);
+
+        let msg = InitMsg {
+            count: 17,
+            reset_admins: vec![
+                HumanAddr::from("alice"),
+                HumanAddr::from("bob"),
+                HumanAddr::from("carol"),
+            ],
+            reset_threshold: 2,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        let _res = init(&mut deps, env, msg).unwrap();
+
+        // the owner alone can no longer reset
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::Reset { count: 4 });
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                "resets need the approval of 2 reset admins, use ProposeReset",
+                msg
+            ),
+            _ => panic!("Must reject an owner reset"),
+        }
+        let env = mock_env(&deps.api, "creator", &[]);
+        let res = handle(&mut deps, env, HandleMsg::ProposeReset { count: 4 });
+        match res {
+            Err(StdError::Unauthorized { .. }) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let env = mock_env(&deps.api, "alice", &[]);
+        let res = handle(&mut deps, env, HandleMsg::ProposeReset { count: 4 }).unwrap();
+        assert_eq!(vec![log("proposal_id", "0")], res.log);
+
+        // one approval is not enough
+        let env = mock_env(&deps.api, "alice", &[]);
+        let msg = HandleMsg::ApproveReset { proposal_id: 0 };
+        let res = handle(&mut deps, env, msg).unwrap();
+        assert_eq!(vec![log("approvals", "1/2")], res.log);
+        let env = mock_env(&deps.api, "alice", &[]);
+        let msg = HandleMsg::ApproveReset { proposal_id: 0 };
+        assert!(handle(&mut deps, env, msg).is_err());
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Odd Number: 17", value);
+
+        // the second distinct admin executes it
+        let env = mock_env(&deps.api, "bob", &[]);
+        let msg = HandleMsg::ApproveReset { proposal_id: 0 };
+        let res = handle(&mut deps, env, msg).unwrap();
+        assert!(res.log.contains(&log("approvals", "2/2")));
+        let res = query(&deps, QueryMsg::QueryEvenOdd {}).unwrap();
+        let value: String = from_binary(&res).unwrap();
+        assert_eq!("Even Number: 4", value);
+
+        let msg = QueryMsg::QueryResetProposal { proposal_id: 0 };
+        let res = query(&deps, msg).unwrap();
+        let value: ResetProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ResetProposalResponse {
+                count: 4,
+                approvals: vec![HumanAddr::from("alice"), HumanAddr::from("bob")],
+                threshold: 2,
+                executed: true,
+            },
+            value
+        );
+
+        // an executed proposal cannot run again
+        let env = mock_env(&deps.api, "carol", &[]);
+        let msg = HandleMsg::ApproveReset { proposal_id: 0 };
+        let res = handle(&mut deps, env, msg);
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => {
+                assert_eq!("reset proposal 0 was already executed", msg)
+            }
+            _ => panic!("Must reject an executed proposal"),
+        }
+        let env = mock_env(&deps.api, "carol", &[]);
+        let msg = HandleMsg::ApproveReset { proposal_id: 1 };
+        assert!(handle(&mut deps, env, msg).is_err());
+
+        let invalid = vec![
+            InitMsg {
+                reset_threshold: 1,
+                ..InitMsg::default()
+            },
+            InitMsg {
+                reset_admins: vec![HumanAddr::from("alice")],
+                reset_threshold: 2,
+                ..InitMsg::default()
+            },
+            InitMsg {
+                reset_admins: vec![HumanAddr::from("alice")],
+                ..InitMsg::default()
+            },
+        ];
+        for msg in invalid {
+            assert!(validate_init_msg(&msg).is_err(), "Must reject {:?}", msg);
+        }
+        let mut deps = mock_dependencies(20, &[]);
+        let msg = InitMsg {
+            reset_admins: vec![HumanAddr::from("alice"), HumanAddr::from("alice")],
+            reset_threshold: 1,
+            ..InitMsg::default()
+        };
+        let env = mock_env(&deps.api, "creator", &[]);
+        assert!(init(&mut deps, env, msg).is_err());
+    }
 }
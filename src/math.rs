@@ -0,0 +1,216 @@
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+use cosmwasm_std::{StdError, StdResult};
+
+pub fn add_checked(a: i32, b: i32) -> StdResult<i32> {
+    a.checked_add(b).ok_or_else(|| overflow(a, "+", b))
+}
+
+pub fn sub_checked(a: i32, b: i32) -> StdResult<i32> {
+    a.checked_sub(b).ok_or_else(|| overflow(a, "-", b))
+}
+
+pub fn mul_checked(a: i32, b: i32) -> StdResult<i32> {
+    a.checked_mul(b).ok_or_else(|| overflow(a, "*", b))
+}
+
+// count changed by count * bps / 10_000, the change rounded toward zero
+pub fn adjust_by_bps(count: i32, bps: i32) -> StdResult<i32> {
+    i32::try_from(bps_adjusted(i64::from(count), bps)).map_err(|_| bps_overflow(count, bps))
+}
+
+pub fn saturating_adjust_by_bps(count: i32, bps: i32) -> i32 {
+    bps_adjusted(i64::from(count), bps)
+        .max(i64::from(i32::MIN))
+        .min(i64::from(i32::MAX)) as i32
+}
+
+pub fn adjust_unsigned_by_bps(count: u64, bps: i32) -> StdResult<u64> {
+    let adjusted = i128::from(count) + i128::from(count) * i128::from(bps) / 10_000;
+    u64::try_from(adjusted).map_err(|_| bps_overflow(count, bps))
+}
+
+fn bps_adjusted(count: i64, bps: i32) -> i64 {
+    // cannot overflow, |count * bps| < 2^62
+    count + count * i64::from(bps) / 10_000
+}
+
+// applies a signed delta to the count of an unsigned game
+pub fn add_unsigned(count: u64, delta: i64) -> StdResult<u64> {
+    if delta >= 0 {
+        count
+            .checked_add(delta as u64)
+            .ok_or_else(|| overflow(count, "+", delta))
+    } else {
+        count
+            .checked_sub((-delta) as u64)
+            .ok_or_else(|| StdError::generic_err("count cannot go below zero"))
+    }
+}
+
+// Miller-Rabin, witnesses up to 37 are exact for every u64, so the cost stays at a
+// few hundred modular multiplications however large the count gets
+pub fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for &p in WITNESSES.iter() {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+    'witnesses: for &a in WITNESSES.iter() {
+        let mut x = pow_mod(a, odd, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..shift {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witnesses;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(modulus)) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1;
+    base %= modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+pub fn is_perfect_square(n: u64) -> bool {
+    let root = isqrt(n);
+    root * root == n
+}
+
+// floor(sqrt(n)) by Newton's method on integers, starting from n / 2 + 1 which is never
+// below the root
+fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    let mut root = n / 2 + 1;
+    let mut next = (root + n / root) / 2;
+    while next < root {
+        root = next;
+        next = (root + n / root) / 2;
+    }
+    root
+}
+
+fn overflow<A: Display, B: Display>(a: A, op: &str, b: B) -> StdError {
+    StdError::generic_err(format!("count overflow: {} {} {}", a, op, b))
+}
+
+fn bps_overflow<A: Display>(count: A, bps: i32) -> StdError {
+    StdError::generic_err(format!("count overflow: {} adjusted by {} bps", count, bps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_overflow(res: StdResult<i32>, expected: &str) {
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(expected, msg),
+            _ => panic!("Must return overflow error"),
+        }
+    }
+
+    #[test]
+    fn add() {
+        assert_eq!(i32::MAX, add_checked(i32::MAX, 0).unwrap());
+        assert_eq!(-1, add_checked(i32::MAX, i32::MIN).unwrap());
+        assert_eq!(0, add_checked(0, 0).unwrap());
+        assert_overflow(add_checked(i32::MAX, 1), "count overflow: 2147483647 + 1");
+        assert_overflow(
+            add_checked(i32::MIN, -1),
+            "count overflow: -2147483648 + -1",
+        );
+    }
+
+    #[test]
+    fn sub() {
+        assert_eq!(i32::MIN, sub_checked(i32::MIN, 0).unwrap());
+        assert_eq!(-1, sub_checked(0, 1).unwrap());
+        assert_eq!(0, sub_checked(0, 0).unwrap());
+        assert_overflow(sub_checked(i32::MIN, 1), "count overflow: -2147483648 - 1");
+        assert_overflow(sub_checked(0, i32::MIN), "count overflow: 0 - -2147483648");
+    }
+
+    #[test]
+    fn mul() {
+        assert_eq!(0, mul_checked(i32::MAX, 0).unwrap());
+        assert_eq!(i32::MIN, mul_checked(i32::MIN, 1).unwrap());
+        assert_eq!(-i32::MAX, mul_checked(i32::MAX, -1).unwrap());
+        assert_overflow(mul_checked(i32::MAX, 2), "count overflow: 2147483647 * 2");
+        assert_overflow(
+            mul_checked(i32::MIN, -1),
+            "count overflow: -2147483648 * -1",
+        );
+    }
+
+    #[test]
+    fn unsigned() {
+        assert_eq!(u64::MAX, add_unsigned(u64::MAX - 1, 1).unwrap());
+        assert_eq!(0, add_unsigned(1, -1).unwrap());
+        assert!(add_unsigned(u64::MAX, 1).is_err());
+        assert!(add_unsigned(0, -1).is_err());
+    }
+
+    #[test]
+    fn bps() {
+        assert_eq!(110, adjust_by_bps(100, 1_000).unwrap());
+        assert_eq!(0, adjust_by_bps(100, -10_000).unwrap());
+        // -1.5 rounds toward zero
+        assert_eq!(-16, adjust_by_bps(-15, 1_000).unwrap());
+        assert_eq!(i32::MAX, adjust_by_bps(i32::MAX, 0).unwrap());
+        assert_overflow(
+            adjust_by_bps(i32::MAX, 1),
+            "count overflow: 2147483647 adjusted by 1 bps",
+        );
+        assert_eq!(i32::MAX, saturating_adjust_by_bps(i32::MAX, 1));
+        assert_eq!(i32::MIN, saturating_adjust_by_bps(i32::MIN, 10_000));
+        assert_eq!(u64::MAX, adjust_unsigned_by_bps(u64::MAX, 0).unwrap());
+        assert!(adjust_unsigned_by_bps(u64::MAX, 1).is_err());
+        assert!(adjust_unsigned_by_bps(1, -20_000).is_err());
+    }
+
+    #[test]
+    fn classifiers() {
+        let primes: Vec<u64> = (0..30).filter(|&n| is_prime(n)).collect();
+        assert_eq!(vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29], primes);
+        assert!(is_prime(2_147_483_647));
+        assert!(!is_prime(25));
+        assert!(!is_prime(3_215_031_751));
+        // a Carmichael number and the largest u64 prime
+        assert!(!is_prime(561));
+        assert!(is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(u64::MAX));
+
+        let squares: Vec<u64> = (0..50).filter(|&n| is_perfect_square(n)).collect();
+        assert_eq!(vec![0, 1, 4, 9, 16, 25, 36, 49], squares);
+        assert!(is_perfect_square(4_294_967_295 * 4_294_967_295));
+        assert!(!is_perfect_square(u64::MAX));
+        assert_eq!(4_294_967_295, isqrt(u64::MAX));
+        assert_eq!(3, isqrt(15));
+    }
+}
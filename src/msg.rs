@@ -1,23 +1,467 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+use cosmwasm_std::{Binary, CanonicalAddr, Coin, HumanAddr};
+
+use crate::state::{AuditEntry, OwnerAction};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct InitMsg {
     pub count: i32,
+    // contract notified with SubscriberMsg::ParityChanged whenever a handler flips the parity
+    pub subscriber: Option<HumanAddr>,
+    // store the count as a u64 that can never go below zero
+    #[serde(default)]
+    pub unsigned: bool,
+    // saturate at i32::MAX / i32::MIN instead of failing on overflow
+    #[serde(default)]
+    pub clamp_on_overflow: bool,
+    // minimum number of blocks between two owner resets
+    #[serde(default)]
+    pub reset_cooldown_blocks: u64,
+    // added to the count for every block elapsed since the previous handler call
+    #[serde(default)]
+    pub auto_drift_per_block: i32,
+    // address named in a `notify_on_reset` log of every reset
+    pub notify_on_reset: Option<HumanAddr>,
+    // blocks after init during which the count cannot be changed yet
+    pub activation_delay_blocks: Option<u64>,
+    // a handler call ending below `safe_floor` must attach `decrease_penalty` for every
+    // unit it moves the count below the floor, the fee goes to the treasury, drift and
+    // owner resets are free
+    pub safe_floor: Option<i32>,
+    pub decrease_penalty: Option<Coin>,
+    // the only denom IncreaseByDeposit counts, the handler is disabled while unset
+    pub deposit_denom: Option<String>,
+    // with a threshold set, a reset needs that many of the admins to approve it and the
+    // owner can no longer Reset or ScheduleReset alone
+    #[serde(default)]
+    pub reset_admins: Vec<HumanAddr>,
+    #[serde(default)]
+    pub reset_threshold: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum HandleMsg {
-    Increase { value: i32 },
-    Decrease { value: i32 },
-    Reset { count: i32 },
+    Increase {
+        value: i32,
+    },
+    Decrease {
+        value: i32,
+    },
+    Reset {
+        count: i32,
+    },
+    // owner only, while frozen every handler that changes the count is rejected
+    FreezeCount {},
+    UnfreezeCount {},
+    // changes the count by count * bps / 10000, rounded toward zero
+    AdjustByPercent {
+        bps: i32,
+    },
+    // moves the count one step to the other parity, +1 unless the count is at its maximum
+    FlipParity {},
+    // adds the attached funds to the treasury without playing
+    Donate {},
+    // owner only, resets the count to `count` in the first handler at or after `at_height`
+    ScheduleReset {
+        count: i32,
+        at_height: u64,
+    },
+    CancelScheduledReset {},
+    // Increase that fails without changing anything unless the count has the
+    // `expected` parity, checked after drift and any due scheduled reset
+    IncreaseIfParity {
+        value: i32,
+        expected: Parity,
+    },
+    // owner only and irreversible, leaves the contract without an owner so no owner-only
+    // handler can ever be called again, `confirm` must be true
+    RenounceOwnership {
+        confirm: bool,
+    },
+    // owner only, seeds a fresh deployment with the count and audit trail exported from
+    // an older contract, allowed once and only before the count changed or the owner
+    // made any other call
+    ImportState {
+        count: i32,
+        history: Vec<AuditEntry>,
+    },
+    // owner only, drops the oldest audit entries until at most `keep_last` remain, but no
+    // more than MAX_PRUNE_ENTRIES per call, so a long trail may need several calls
+    PruneHistory {
+        keep_last: u32,
+    },
+    // owner only, inclusive [start_height, end_height] ranges, the count can then only
+    // change inside one of them, an empty list lifts the restriction
+    SetActiveWindows {
+        windows: Vec<(u64, u64)>,
+    },
+    // owner only, the strings QueryGlyph shows for each parity
+    SetGlyphs {
+        even: String,
+        odd: String,
+    },
+    // Increase by the amount of `deposit_denom` attached, the funds go to the treasury
+    IncreaseByDeposit {},
+    // a display name for the sender, replaces any earlier one, need not be unique
+    SetAlias {
+        alias: String,
+    },
+    // reset admin only, a reset to `count` that runs once `reset_threshold` admins approved
+    ProposeReset {
+        count: i32,
+    },
+    // reset admin only, the approval reaching the threshold executes the reset, with the
+    // same cooldown and activation checks as an owner Reset
+    ApproveReset {
+        proposal_id: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum QueryMsg {
     // GetCount returns the current count as a json-encoded number
     QueryEvenOdd {},
+    // name and version of the running build
+    QueryInfo {},
+    // how many more same-parity mutations are needed for a streak of `goal`
+    QueryStreakToGoal {
+        goal: u32,
+    },
+    // configuration a fresh deployment gets for every field left out of its InitMsg
+    QueryDefaults {},
+    // naive odds of the parity the count will show, 50/50 unless it is frozen
+    QueryProbabilityHint {},
+    // round-trips an address through the chain's canonicalization, for debugging
+    QueryNormalizeAddr {
+        addr: HumanAddr,
+    },
+    // every significant event, newest first, skipping the `start` newest
+    QueryAuditTrail {
+        start: Option<u64>,
+        limit: Option<u32>,
+    },
+    // 0 for even, 1 for odd
+    QueryParityBit {},
+    // the active configuration with every optional or implicit setting resolved
+    QueryEffectiveConfig {},
+    // count.rem_euclid(modulus), for exploring the game under other moduli
+    QueryResidue {
+        modulus: i32,
+    },
+    // sha256 of "<count>|<parity>|<height>" as of the last handler that changed the
+    // count, a checksum of the public state and not a signature, anyone can recompute it
+    QueryAttestation {},
+    // smallest and largest count ever reached, with their parities
+    QueryExtremes {},
+    // funds held in the treasury
+    QueryTreasury {},
+    // distinct addresses that ever changed the count
+    QueryParticipantCount {},
+    // parity plus a few other properties of the count, negative counts have none
+    QueryClassify {},
+    // names of the handle and query messages this build accepts, for feature detection
+    QuerySupportedMessages {},
+    // how far the count is from the largest and smallest value its type can hold
+    QueryBoundaryDistance {},
+    // blocks the count spent in each parity, not counting the current, still open stretch
+    QueryParityDwell {},
+    // the count the next handler sees if it runs `blocks` blocks after the last one,
+    // before its own change
+    QueryDriftForecast {
+        blocks: u64,
+    },
+    // owner-only handler calls, paged like QueryAuditTrail
+    QueryOwnerActions {
+        start: Option<u64>,
+        limit: Option<u32>,
+    },
+    // whether the count meets every supplied condition, `min` and `max` are inclusive
+    QueryPredicate {
+        min: Option<i32>,
+        max: Option<i32>,
+        parity: Option<Parity>,
+        divisible_by: Option<i32>,
+    },
+    // number of entries in each growing store, read from counters kept alongside them
+    QueryStorageStats {},
+    // 1-100, from sha256 of the canonical address and the count, changes with the count
+    QueryLuckyNumber {
+        address: HumanAddr,
+    },
+    // whether a handler changing the count would pass the freeze, activation and active
+    // window checks at `height`, queries cannot see the current block themselves
+    QueryMutationStatus {
+        height: u64,
+    },
+    // count.rem_euclid(10), the last digit of a non-negative count
+    QueryLowDigit {},
+    // a short display string for the current parity, "🟦 even" / "🟧 odd" unless the
+    // owner set others
+    QueryGlyph {},
+    // lets a client holding `since_count` skip an update, `changed` is false while the
+    // count still equals it
+    QueryParityIfChanged {
+        since_count: i32,
+    },
+    QueryAlias {
+        address: HumanAddr,
+    },
+    // the stored schedule as seen from `height`, a due reset stays pending with 0 blocks
+    // remaining until the next handler call applies it
+    QueryScheduledReset {
+        height: u64,
+    },
+    QueryResetProposal {
+        proposal_id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledResetResponse {
+    pub pending: bool,
+    pub at_height: Option<u64>,
+    pub blocks_remaining: Option<u64>,
+    pub new_count: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResetProposalResponse {
+    pub count: i32,
+    pub approvals: Vec<HumanAddr>,
+    pub threshold: u32,
+    pub executed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AliasResponse {
+    pub alias: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParityIfChangedResponse {
+    pub changed: bool,
+    pub count: String,
+    pub parity: Parity,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GlyphResponse {
+    pub glyph: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LowDigitResponse {
+    pub digit: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MutationStatusResponse {
+    pub allowed: bool,
+    // the error such a handler would fail with
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LuckyNumberResponse {
+    pub number: u8,
+    pub parity: Parity,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StorageStatsResponse {
+    pub audit_entries: u64,
+    pub owner_actions: u64,
+    pub participants: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PredicateResponse {
+    pub matches: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerActionsResponse {
+    pub actions: Vec<OwnerAction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DriftForecastResponse {
+    pub count: String,
+    pub parity: Parity,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParityDwellResponse {
+    pub even_blocks: u64,
+    pub odd_blocks: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoundaryDistanceResponse {
+    pub to_max: String,
+    pub to_min: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupportedMessagesResponse {
+    pub handle: Vec<String>,
+    pub query: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClassifyResponse {
+    pub parity: Parity,
+    pub is_prime: bool,
+    pub is_perfect_square: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParticipantCountResponse {
+    pub unique_participants: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TreasuryResponse {
+    pub treasury: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExtremesResponse {
+    pub max: String,
+    pub max_parity: Parity,
+    pub min: String,
+    pub min_parity: Parity,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AttestationResponse {
+    pub count: String,
+    pub parity: Parity,
+    pub height: u64,
+    pub hash: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResidueResponse {
+    pub residue: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EffectiveConfigResponse {
+    // None once ownership was renounced
+    pub owner: Option<HumanAddr>,
+    // addresses have no default, None means the feature is off
+    pub subscriber: Option<HumanAddr>,
+    pub notify_on_reset: Option<HumanAddr>,
+    pub unsigned: bool,
+    pub clamp_on_overflow: bool,
+    pub count_frozen: bool,
+    // bounds of the count type, as decimal strings like the count itself
+    pub min_count: String,
+    pub max_count: String,
+    pub reset_cooldown_blocks: u64,
+    // first height at which the owner may reset, 0 before any reset
+    pub next_reset_height: u64,
+    pub auto_drift_per_block: i32,
+    pub max_drift_blocks: u64,
+    pub safe_floor: Option<i32>,
+    pub decrease_penalty: Option<Coin>,
+    pub deposit_denom: Option<String>,
+    // empty when the count may change at any height
+    pub active_windows: Vec<(u64, u64)>,
+    // 0 when the owner resets alone
+    pub reset_threshold: u32,
+    pub reset_admins: Vec<HumanAddr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParityBitResponse {
+    pub bit: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuditTrailResponse {
+    pub events: Vec<AuditEntry>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NormalizeAddrResponse {
+    pub canonical: CanonicalAddr,
+    pub human: HumanAddr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DefaultsResponse {
+    pub init_msg: InitMsg,
+    pub max_drift_blocks: u64,
+    pub schema_version: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InfoResponse {
+    pub name: String,
+    pub version: String,
+    pub schema_version: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreakToGoalResponse {
+    pub streak: u32,
+    pub remaining: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProbabilityHintResponse {
+    pub even_pct: u8,
+    pub odd_pct: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub fn of(count: i32) -> Self {
+        Parity::of_unsigned(count.rem_euclid(2) as u64)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Parity::Even => "even",
+            Parity::Odd => "odd",
+        }
+    }
+
+    pub fn bit(self) -> u8 {
+        match self {
+            Parity::Even => 0,
+            Parity::Odd => 1,
+        }
+    }
+
+    pub fn of_unsigned(count: u64) -> Self {
+        if count % 2 == 0 {
+            Parity::Even
+        } else {
+            Parity::Odd
+        }
+    }
+}
+
+// Handle message sent to the subscriber contract. It is dispatched as a regular
+// WasmMsg::Execute, so a failing subscriber reverts the whole transaction: cosmwasm
+// has no fire-and-forget submessages, subscribers must accept every notification.
+// The count is a decimal string so that the u64 count of unsigned games fits.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriberMsg {
+    ParityChanged { new_parity: Parity, count: String },
+}
@@ -1,15 +1,180 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, Storage};
-use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use cosmwasm_std::{CanonicalAddr, Coin, HumanAddr, StdResult, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, ReadonlySingleton, Singleton,
+};
+
+use crate::msg::Parity;
 
 pub static CONFIG_KEY: &[u8] = b"config";
+pub static AUDIT_PREFIX: &[u8] = b"audit";
+pub static AUDIT_COUNT_KEY: &[u8] = b"audit_count";
+pub static AUDIT_FIRST_KEY: &[u8] = b"audit_first";
+pub static OWNER_ACTION_PREFIX: &[u8] = b"owner_action";
+pub static OWNER_ACTION_COUNT_KEY: &[u8] = b"owner_action_count";
+pub static PARTICIPANT_PREFIX: &[u8] = b"participant";
+pub static ALIAS_PREFIX: &[u8] = b"alias";
+pub static RESET_PROPOSAL_PREFIX: &[u8] = b"reset_proposal";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub count: i32,
+    // empty once ownership was renounced, no sender can match it
     pub owner: CanonicalAddr,
+    pub subscriber: Option<CanonicalAddr>,
+    pub flags: Flags,
+    // unsigned games operate on `unsigned_count` and ignore `count`
+    pub unsigned_count: u64,
+    pub reset_cooldown_blocks: u64,
+    pub last_reset_height: Option<u64>,
+    pub auto_drift_per_block: i32,
+    // height of the last handler that changed the count, drift is applied from there
+    pub last_height: u64,
+    // number of consecutive mutations that ended on `streak_parity`
+    pub streak: u32,
+    pub streak_parity: Option<Parity>,
+    pub notify_on_reset: Option<CanonicalAddr>,
+    // smallest and largest count seen after any mutation, including the initial one
+    pub extremes: Extremes<i32>,
+    pub unsigned_extremes: Extremes<u64>,
+    // first height at which the count may change
+    pub activation_height: u64,
+    pub treasury: Vec<Coin>,
+    // distinct addresses that ever changed the count
+    pub unique_participants: u64,
+    pub scheduled_reset: Option<ScheduledReset>,
+    // blocks spent in each parity up to the last change, and the height of that change
+    pub even_blocks: u64,
+    pub odd_blocks: u64,
+    pub parity_since: u64,
+    pub safe_floor: Option<i32>,
+    pub decrease_penalty: Option<Coin>,
+    pub deposit_denom: Option<String>,
+    pub active_windows: Vec<(u64, u64)>,
+    pub even_glyph: String,
+    pub odd_glyph: String,
+    // resets need `reset_threshold` approvals from these admins while the threshold is set
+    pub reset_admins: Vec<CanonicalAddr>,
+    pub reset_threshold: u32,
+    pub next_proposal_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledReset {
+    pub count: i32,
+    pub at_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub struct Extremes<T> {
+    pub min: T,
+    pub max: T,
+}
+
+impl<T: Copy + Ord> Extremes<T> {
+    pub fn new(count: T) -> Self {
+        Extremes {
+            min: count,
+            max: count,
+        }
+    }
+
+    pub fn record(&mut self, count: T) {
+        self.min = self.min.min(count);
+        self.max = self.max.max(count);
+    }
+}
+
+impl State {
+    pub fn parity(&self) -> Parity {
+        if self.flags.is_unsigned() {
+            Parity::of_unsigned(self.unsigned_count)
+        } else {
+            Parity::of(self.count)
+        }
+    }
+
+    pub fn record_extremes(&mut self) {
+        if self.flags.is_unsigned() {
+            self.unsigned_extremes.record(self.unsigned_count);
+        } else {
+            self.extremes.record(self.count);
+        }
+    }
+
+    // the count of either game type, wide enough to compare and subtract without overflow
+    pub fn wide_count(&self) -> i128 {
+        if self.flags.is_unsigned() {
+            i128::from(self.unsigned_count)
+        } else {
+            i128::from(self.count)
+        }
+    }
+
+    pub fn display_count(&self) -> String {
+        if self.flags.is_unsigned() {
+            self.unsigned_count.to_string()
+        } else {
+            self.count.to_string()
+        }
+    }
+}
+
+const UNSIGNED: u16 = 1;
+const CLAMP_ON_OVERFLOW: u16 = 1 << 1;
+const COUNT_FROZEN: u16 = 1 << 2;
+const IMPORTED: u16 = 1 << 3;
+
+// boolean settings packed into a single integer to keep the stored State small
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub struct Flags(u16);
+
+impl Flags {
+    pub fn is_unsigned(self) -> bool {
+        self.get(UNSIGNED)
+    }
+
+    pub fn set_unsigned(&mut self, on: bool) {
+        self.set(UNSIGNED, on)
+    }
+
+    pub fn is_clamp_on_overflow(self) -> bool {
+        self.get(CLAMP_ON_OVERFLOW)
+    }
+
+    pub fn set_clamp_on_overflow(&mut self, on: bool) {
+        self.set(CLAMP_ON_OVERFLOW, on)
+    }
+
+    pub fn is_count_frozen(self) -> bool {
+        self.get(COUNT_FROZEN)
+    }
+
+    pub fn set_count_frozen(&mut self, on: bool) {
+        self.set(COUNT_FROZEN, on)
+    }
+
+    pub fn is_imported(self) -> bool {
+        self.get(IMPORTED)
+    }
+
+    pub fn set_imported(&mut self, on: bool) {
+        self.set(IMPORTED, on)
+    }
+
+    fn get(self, flag: u16) -> bool {
+        self.0 & flag != 0
+    }
+
+    fn set(&mut self, flag: u16, on: bool) {
+        if on {
+            self.0 |= flag;
+        } else {
+            self.0 &= !flag;
+        }
+    }
 }
 
 pub fn config<S: Storage>(storage: &mut S) -> Singleton<S, State> {
@@ -19,3 +184,176 @@ pub fn config<S: Storage>(storage: &mut S) -> Singleton<S, State> {
 pub fn config_read<S: Storage>(storage: &S) -> ReadonlySingleton<S, State> {
     singleton_read(storage, CONFIG_KEY)
 }
+
+// also taken as input by ImportState, so as strict as the messages themselves
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum AuditEvent {
+    Increase { value: i32 },
+    Decrease { value: i32 },
+    Reset { count: i32 },
+    AdjustByPercent { bps: i32 },
+    FlipParity { step: i32 },
+    Donate { amount: Vec<Coin> },
+    ScheduleReset { count: i32, at_height: u64 },
+    ImportState { count: i32, entries: u64 },
+    CancelScheduledReset {},
+    RenounceOwnership {},
+    PruneHistory { keep_last: u32 },
+    SetActiveWindows { windows: Vec<(u64, u64)> },
+    SetGlyphs { even: String, odd: String },
+    IncreaseByDeposit { amount: Coin },
+    SetAlias { alias: String },
+    SetCountFrozen { frozen: bool },
+    ProposeReset { proposal_id: u64, count: i32 },
+    ApproveReset { proposal_id: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AuditEntry {
+    pub height: u64,
+    pub sender: HumanAddr,
+    pub event: AuditEvent,
+    // count right after the event
+    pub count: String,
+}
+
+// number of audit entries ever appended, entries are keyed by their index
+pub fn audit_count<S: Storage>(storage: &S) -> StdResult<u64> {
+    Ok(singleton_read(storage, AUDIT_COUNT_KEY)
+        .may_load()?
+        .unwrap_or(0))
+}
+
+pub fn append_audit_entry<S: Storage>(storage: &mut S, entry: &AuditEntry) -> StdResult<()> {
+    let index = audit_count(storage)?;
+    bucket(AUDIT_PREFIX, storage).save(&index.to_be_bytes(), entry)?;
+    singleton(storage, AUDIT_COUNT_KEY).save(&(index + 1))
+}
+
+pub fn audit_entry<S: Storage>(storage: &S, index: u64) -> StdResult<AuditEntry> {
+    bucket_read(AUDIT_PREFIX, storage).load(&index.to_be_bytes())
+}
+
+// index of the oldest entry still stored, everything before it was pruned
+pub fn audit_first<S: Storage>(storage: &S) -> StdResult<u64> {
+    Ok(singleton_read(storage, AUDIT_FIRST_KEY)
+        .may_load()?
+        .unwrap_or(0))
+}
+
+// removes up to `max` of the oldest entries so that at most `keep_last` remain,
+// returns how many were removed
+pub fn prune_audit_entries<S: Storage>(
+    storage: &mut S,
+    keep_last: u64,
+    max: u64,
+) -> StdResult<u64> {
+    let first = audit_first(storage)?;
+    let until = audit_count(storage)?
+        .saturating_sub(keep_last)
+        .min(first.saturating_add(max))
+        .max(first);
+    let mut entries = bucket::<_, AuditEntry>(AUDIT_PREFIX, storage);
+    for index in first..until {
+        entries.remove(&index.to_be_bytes());
+    }
+    singleton(storage, AUDIT_FIRST_KEY).save(&until)?;
+    Ok(until - first)
+}
+
+// owner-only handler calls, kept apart from the audit trail so they are not buried
+// under gameplay events
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerAction {
+    pub height: u64,
+    pub action: AuditEvent,
+}
+
+pub fn owner_action_count<S: Storage>(storage: &S) -> StdResult<u64> {
+    Ok(singleton_read(storage, OWNER_ACTION_COUNT_KEY)
+        .may_load()?
+        .unwrap_or(0))
+}
+
+pub fn append_owner_action<S: Storage>(storage: &mut S, action: &OwnerAction) -> StdResult<()> {
+    let index = owner_action_count(storage)?;
+    bucket(OWNER_ACTION_PREFIX, storage).save(&index.to_be_bytes(), action)?;
+    singleton(storage, OWNER_ACTION_COUNT_KEY).save(&(index + 1))
+}
+
+pub fn owner_action<S: Storage>(storage: &S, index: u64) -> StdResult<OwnerAction> {
+    bucket_read(OWNER_ACTION_PREFIX, storage).load(&index.to_be_bytes())
+}
+
+// marks `addr` as a participant, true if it was not one yet
+pub fn mark_participant<S: Storage>(storage: &mut S, addr: &CanonicalAddr) -> StdResult<bool> {
+    let mut seen = bucket(PARTICIPANT_PREFIX, storage);
+    if seen.may_load(addr.as_slice())?.unwrap_or(false) {
+        return Ok(false);
+    }
+    seen.save(addr.as_slice(), &true)?;
+    Ok(true)
+}
+
+pub fn save_alias<S: Storage>(storage: &mut S, addr: &CanonicalAddr, alias: &str) -> StdResult<()> {
+    bucket(ALIAS_PREFIX, storage).save(addr.as_slice(), &alias.to_string())
+}
+
+pub fn alias<S: Storage>(storage: &S, addr: &CanonicalAddr) -> StdResult<Option<String>> {
+    bucket_read(ALIAS_PREFIX, storage).may_load(addr.as_slice())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResetProposal {
+    pub count: i32,
+    // distinct admins in the order they approved
+    pub approvals: Vec<CanonicalAddr>,
+    pub executed: bool,
+}
+
+pub fn save_reset_proposal<S: Storage>(
+    storage: &mut S,
+    id: u64,
+    proposal: &ResetProposal,
+) -> StdResult<()> {
+    bucket(RESET_PROPOSAL_PREFIX, storage).save(&id.to_be_bytes(), proposal)
+}
+
+pub fn reset_proposal<S: Storage>(storage: &S, id: u64) -> StdResult<Option<ResetProposal>> {
+    bucket_read(RESET_PROPOSAL_PREFIX, storage).may_load(&id.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_are_independent() {
+        let mut flags = Flags::default();
+        assert!(!flags.is_unsigned());
+        assert!(!flags.is_clamp_on_overflow());
+        assert!(!flags.is_count_frozen());
+
+        flags.set_unsigned(true);
+        flags.set_count_frozen(true);
+        assert!(flags.is_unsigned());
+        assert!(!flags.is_clamp_on_overflow());
+        assert!(flags.is_count_frozen());
+
+        flags.set_clamp_on_overflow(true);
+        flags.set_unsigned(false);
+        assert!(!flags.is_unsigned());
+        assert!(flags.is_clamp_on_overflow());
+        assert!(flags.is_count_frozen());
+
+        // setting an already set flag is a no-op
+        flags.set_count_frozen(true);
+        assert_eq!(Flags(CLAMP_ON_OVERFLOW | COUNT_FROZEN), flags);
+
+        flags.set_count_frozen(false);
+        flags.set_clamp_on_overflow(false);
+        assert_eq!(Flags::default(), flags);
+    }
+}